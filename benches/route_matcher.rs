@@ -0,0 +1,55 @@
+//! Compares resolving a concrete route with a [`RouteMatcher`] against
+//! calling [`ParameterizedRoute::match_route`] on each template in turn.
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use web_route::{ParameterizedRoute, RouteMatcher, WebRoute};
+
+/// Builds `count` templates resembling the routes of a REST API.
+fn templates(count: usize) -> Vec<ParameterizedRoute> {
+    (0..count)
+        .map(|i| match i % 4 {
+            0 => ParameterizedRoute::new(format!("/api/v1/resource_{i}")),
+            1 => ParameterizedRoute::new(format!("/api/v1/resource_{i}/{{id}}")),
+            2 => ParameterizedRoute::new(format!(
+                "/api/v1/resource_{i}/{{id}}/children/{{child_id}}"
+            )),
+            _ => ParameterizedRoute::new(format!("/api/v1/resource_{i}/files/{{*path}}")),
+        })
+        .collect()
+}
+
+fn match_last_template(c: &mut Criterion) {
+    let mut group = c.benchmark_group("match_last_template");
+
+    for count in [10, 100, 500] {
+        let templates = templates(count);
+        let mut matcher = RouteMatcher::new();
+        for (i, template) in templates.iter().enumerate() {
+            matcher
+                .insert(template.clone(), i)
+                .expect("templates should not conflict");
+        }
+
+        // The last template is the worst case for the linear approach.
+        let route = WebRoute::new(format!("/api/v1/resource_{}/files/a/b/c.txt", count - 1));
+
+        group.bench_with_input(
+            BenchmarkId::new("route_matcher", count),
+            &route,
+            |b, route| b.iter(|| matcher.at(black_box(route))),
+        );
+
+        group.bench_with_input(BenchmarkId::new("linear", count), &route, |b, route| {
+            b.iter(|| {
+                templates
+                    .iter()
+                    .find_map(|template| template.match_route(black_box(route)))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, match_last_template);
+criterion_main!(benches);
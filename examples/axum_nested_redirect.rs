@@ -1,24 +1,19 @@
 //! An example application that uses the same [`WebRoute`]s to define both an
 //! `axum` router and build out a populated redirect route.
 
-use std::sync::LazyLock;
-
 use axum::{
     Router,
     extract::Path,
+    http::StatusCode,
     response::{Html, Redirect},
     routing::get,
 };
-use web_route::ParameterizedRoute;
+use web_route::{ParameterizedRoute, RouteParams, route};
 
-// Would be cool if we could make this able to be evaluated at compile time so
-// that this can be a static without `LazyLock`.
-static FOO_ROUTE: LazyLock<ParameterizedRoute> =
-    LazyLock::new(|| ParameterizedRoute::new("/foo/{foo_id}"));
-static BAR_ROUTE: LazyLock<ParameterizedRoute> =
-    LazyLock::new(|| ParameterizedRoute::new("/bar/{bar_id}"));
-static BAZ_ROUTE: LazyLock<ParameterizedRoute> =
-    LazyLock::new(|| ParameterizedRoute::new("/baz/{bar_id}"));
+// Parsed and normalized at compile time, so there is no need for a `LazyLock`.
+static FOO_ROUTE: ParameterizedRoute = route!("/foo/{foo_id}");
+static BAR_ROUTE: ParameterizedRoute = route!("/bar/{bar_id}");
+static BAZ_ROUTE: ParameterizedRoute = route!("/baz/{bar_id}");
 
 fn build_router() -> Router {
     // Using the `WebRoute` to define axum server routes.
@@ -29,23 +24,26 @@ fn build_router() -> Router {
     Router::new().nest(&FOO_ROUTE, nested_router)
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct RouteParams {
+// Deriving `RouteParams` checks at compile time that the fields line up with
+// the parameters of the full route to the `baz` handler.
+#[derive(Debug, serde::Deserialize, RouteParams)]
+#[route("/foo/{foo_id}/baz/{bar_id}")]
+struct FooBazParams {
     foo_id: String,
     bar_id: String,
 }
 
-async fn bar_handler(Path(params): Path<RouteParams>) -> Redirect {
-    Redirect::to(
-        // Using the `WebRoute` to populate the redirect route.
-        &FOO_ROUTE
-            .join(BAZ_ROUTE.clone())
-            .to_web_route(&params)
-            .unwrap(),
-    )
+async fn bar_handler(Path(params): Path<FooBazParams>) -> Result<Redirect, StatusCode> {
+    // axum does not match empty path segments, so the parameters are never
+    // empty.
+    let route = params
+        .to_web_route()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Redirect::to(&route))
 }
 
-async fn baz_handler(Path(params): Path<RouteParams>) -> Html<String> {
+async fn baz_handler(Path(params): Path<FooBazParams>) -> Html<String> {
     Html(format!("<h1>{params:?}</h1>"))
 }
 
@@ -0,0 +1,132 @@
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
+
+use crate::{
+    ParameterizedRoute, RouteMatcher, ToRouteSegment, WebRoute, error::WebRouteError,
+    utils::encode_segment,
+};
+
+/// Creates a [`ParameterizedRoute`] from a template that has already been
+/// normalized at compile time, along with its path in the syntax of axum.
+pub const fn parameterized_route(
+    normalized: &'static str,
+    path: &'static str,
+) -> ParameterizedRoute {
+    ParameterizedRoute {
+        template: Cow::Borrowed(normalized),
+        path: Cow::Borrowed(path),
+    }
+}
+
+/// Creates a [`WebRoute`] from a route that has already been normalized at
+/// compile time.
+pub const fn fixed_route(normalized: &'static str) -> WebRoute {
+    WebRoute(Cow::Borrowed(normalized))
+}
+
+/// A segment of a template whose parameters have been resolved to values at
+/// compile time.
+pub enum PopulatedSegment<'a> {
+    NamedParam(&'static str, &'a dyn ToRouteSegment),
+    OptionalParam(&'static str, Option<&'a dyn ToRouteSegment>),
+    CatchallParam(&'a dyn ToRouteSegment),
+    Static(&'static str),
+    /// The parts of a segment mixing static text and named parameters.
+    Mixed(&'a [PopulatedSegment<'a>]),
+}
+
+/// Builds a [`WebRoute`] from `segments` and `query_params`, encoding the
+/// parameter values in the same way as [`ParameterizedRoute::to_web_route`].
+///
+/// Query parameters without a value are omitted. Optional parameters are only
+/// ever followed by other optional parameters, so the route ends at the first
+/// one without a value.
+///
+/// # Errors
+///
+/// - [`WebRouteError::EmptyParamValue`] if the value of a named parameter
+///   (including an optional one, or one within a mixed segment) is empty.
+pub fn populated_route(
+    segments: &[PopulatedSegment<'_>],
+    query_params: &[(&'static str, Option<&dyn ToRouteSegment>)],
+) -> Result<WebRoute, WebRouteError> {
+    let mut populated_segments = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let populated = match segment {
+            PopulatedSegment::NamedParam(name, value)
+            | PopulatedSegment::OptionalParam(name, Some(value)) => populated_param(name, *value)?,
+            PopulatedSegment::OptionalParam(_, None) => break,
+            PopulatedSegment::CatchallParam(value) => value
+                .to_route_segment()
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(encode_segment)
+                .collect::<Vec<_>>()
+                .join("/"),
+            PopulatedSegment::Static(value) => (*value).to_owned(),
+            PopulatedSegment::Mixed(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    PopulatedSegment::NamedParam(name, value) => populated_param(name, *value),
+                    PopulatedSegment::Static(value) => Ok((*value).to_owned()),
+                    _ => {
+                        unreachable!("mixed segments only contain static text and named parameters")
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        populated_segments.push(populated);
+    }
+
+    let query_pairs = query_params
+        .iter()
+        .filter_map(|(param, value)| value.map(|value| (*param, value.to_route_segment())))
+        .collect::<Vec<_>>();
+    let query = serde_urlencoded::to_string(query_pairs)
+        .expect("string key-value pairs should always be form-encodable");
+
+    Ok(WebRoute::new(format!(
+        "/{}?{query}",
+        populated_segments.join("/")
+    )))
+}
+
+/// Percent-encodes the value of the named parameter `name`, rejecting an empty
+/// value in the same way as [`ParameterizedRoute::to_web_route`].
+fn populated_param(name: &str, value: &dyn ToRouteSegment) -> Result<String, WebRouteError> {
+    let value = value.to_route_segment();
+    if value.is_empty() {
+        return Err(WebRouteError::EmptyParamValue(name.to_owned()));
+    }
+
+    Ok(encode_segment(&value))
+}
+
+/// Builds a [`RouteMatcher`] associating each of `routes` with its index.
+pub fn route_matcher<const N: usize>(routes: [ParameterizedRoute; N]) -> RouteMatcher<usize> {
+    let mut matcher = RouteMatcher::new();
+    for (index, route) in routes.into_iter().enumerate() {
+        matcher
+            .insert(route, index)
+            .expect("variant routes should have been checked for conflicts at compile time");
+    }
+
+    matcher
+}
+
+/// Parses the captured value of a required parameter, which is `None` if it
+/// was not captured or cannot be parsed.
+pub fn parse_param<T: FromStr>(params: &HashMap<String, String>, name: &str) -> Option<T> {
+    params.get(name)?.parse().ok()
+}
+
+/// Parses the captured value of an optional parameter, which is `Some(None)`
+/// if it was not captured, and `None` if it cannot be parsed.
+pub fn parse_optional_param<T: FromStr>(
+    params: &HashMap<String, String>,
+    name: &str,
+) -> Option<Option<T>> {
+    match params.get(name) {
+        Some(value) => value.parse().ok().map(Some),
+        None => Some(None),
+    }
+}
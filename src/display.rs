@@ -0,0 +1,177 @@
+//! Serializes a field by its [`ToRouteSegment`] representation (i.e. its
+//! [`Display`](fmt::Display) representation, for anything that implements
+//! it), for use with `#[serde(with = "web_route::display")]`.
+//!
+//! When a route is populated from a `serde::Serialize` struct, only fields
+//! that serialize into strings, numbers, bools and unit enum variants populate
+//! a parameter. This allows anything that implements `ToRouteSegment` (e.g.
+//! dates, decimals or enums with data) to be used instead, and parses it back
+//! via [`FromStr`] when the struct is deserialized from a matched route. A
+//! type that cannot be parsed back can use
+//! `#[serde(serialize_with = "web_route::display::serialize")]` on its own.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::{fmt, str::FromStr};
+//!
+//! use serde::{Deserialize, Serialize};
+//! use web_route::ParameterizedRoute;
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Version {
+//!     Latest,
+//!     Tagged(u32),
+//! }
+//!
+//! impl fmt::Display for Version {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         match self {
+//!             Version::Latest => write!(f, "latest"),
+//!             Version::Tagged(tag) => write!(f, "v{tag}"),
+//!         }
+//!     }
+//! }
+//!
+//! impl FromStr for Version {
+//!     type Err = String;
+//!
+//!     fn from_str(value: &str) -> Result<Self, Self::Err> {
+//!         match value.strip_prefix('v') {
+//!             Some(tag) => tag.parse().map(Version::Tagged).map_err(|_| value.to_owned()),
+//!             None if value == "latest" => Ok(Version::Latest),
+//!             None => Err(value.to_owned()),
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Params {
+//!     #[serde(with = "web_route::display")]
+//!     version: Version,
+//! }
+//!
+//! let route = ParameterizedRoute::new("/docs/{version}");
+//! let params = Params { version: Version::Tagged(2) };
+//!
+//! let web_route = route.to_web_route(&params).unwrap();
+//!
+//! assert_eq!(&*web_route, "/docs/v2");
+//! assert_eq!(route.match_route_as(&web_route).unwrap(), Some(params));
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serializer, de};
+
+use crate::ToRouteSegment;
+
+/// Serializes `value` as a string, via its [`ToRouteSegment`]
+/// representation.
+///
+/// # Errors
+///
+/// Any error of the `serializer`.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ToRouteSegment + ?Sized,
+    S: Serializer,
+{
+    serializer.serialize_str(&value.to_route_segment())
+}
+
+/// Deserializes a value from a string, via [`FromStr`].
+///
+/// # Errors
+///
+/// Any error of the `deserializer`, or the error of [`FromStr`] if the string
+/// cannot be parsed.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+
+    value.parse().map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod display_tests {
+    use crate::{ParameterizedRoute, WebRoute};
+
+    /// Serialized by serde as a map, which does not populate a parameter.
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Amount {
+        cents: u64,
+    }
+
+    impl std::fmt::Display for Amount {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}.{:02}", self.cents / 100, self.cents % 100)
+        }
+    }
+
+    impl std::str::FromStr for Amount {
+        type Err = String;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            let (units, cents) = value.split_once('.').ok_or("missing `.`")?;
+            let parse = |value: &str| value.parse::<u64>().map_err(|err| err.to_string());
+
+            Ok(Amount {
+                cents: parse(units)? * 100 + parse(cents)?,
+            })
+        }
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Params {
+        #[serde(with = "crate::display")]
+        amount: Amount,
+    }
+
+    #[derive(serde::Serialize)]
+    struct UnannotatedParams {
+        amount: Amount,
+    }
+
+    #[test]
+    fn should_populate_from_display_representation() {
+        // Arrange
+        let route = ParameterizedRoute::new("/payments/{amount}");
+        let params = Params {
+            amount: Amount { cents: 1234 },
+        };
+
+        // Act
+        let annotated = route.to_web_route(&params);
+        let unannotated = route.to_web_route(&UnannotatedParams {
+            amount: Amount { cents: 1234 },
+        });
+
+        // Assert
+        assert_eq!(annotated.unwrap(), WebRoute::new("/payments/12.34"));
+        assert!(unannotated.is_err());
+    }
+
+    #[test]
+    fn should_parse_matched_value_from_str() {
+        // Arrange
+        let route = ParameterizedRoute::new("/payments/{amount}");
+
+        // Act
+        let valid = route.match_route_as::<Params, _>("/payments/12.34");
+        let invalid = route.match_route_as::<Params, _>("/payments/twelve");
+
+        // Assert
+        assert_eq!(
+            valid.unwrap(),
+            Some(Params {
+                amount: Amount { cents: 1234 },
+            })
+        );
+        assert!(invalid.is_err());
+    }
+}
@@ -1,12 +1,315 @@
+use crate::{parameterized_route::dialect::Dialect, route_set::RouteConflict};
+
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum WebRouteError {
     /// When generating a populated route, the `value`s input needs to
     /// serialize into key-value pairs.
-    #[error("values need to be able to serialize into a `serde_json::Value::Object`")]
+    #[error("values need to serialize into key-value pairs")]
     InvalidValue,
 
     /// When generating a populated route, there was no value to populate the
     /// specified parameter key.
     #[error("no value to populate parameter: {0}")]
     UnpopulatedParam(String),
+
+    /// When generating a populated route, some of the template's parameters
+    /// had no value or, when populating strictly, some of the values did not
+    /// populate any parameter.
+    #[error(
+        "unable to populate `{template}`: {}",
+        describe_mismatch(missing, unused)
+    )]
+    MismatchedParams {
+        /// The full template that was being populated.
+        template: String,
+        /// The names of the parameters that had no value.
+        missing: Vec<String>,
+        /// The names of the values that did not populate any parameter. Only
+        /// checked when populating strictly.
+        unused: Vec<String>,
+    },
+
+    /// When generating a populated route, the value of a named parameter was
+    /// empty, which would leave its segment out of the route.
+    #[error("empty value for parameter: {0}")]
+    EmptyParamValue(String),
+
+    /// The parameters captured when matching a route could not be deserialized
+    /// into the requested type.
+    #[error("unable to deserialize matched parameters: {0}")]
+    InvalidParams(String),
+
+    /// The `query` could not be form-encoded. It needs to serialize into
+    /// key-value pairs with scalar values.
+    #[error("unable to form-encode query: {0}")]
+    InvalidQuery(String),
+
+    /// A segment of the route is malformed. Only returned by the strict
+    /// `parse` constructors.
+    #[error("invalid segment `{segment}` at position {position}: {reason}")]
+    InvalidSegment {
+        /// The position of the segment, ignoring any empty segments.
+        position: usize,
+        /// The offending segment.
+        segment: String,
+        /// Why the segment is invalid.
+        reason: SyntaxError,
+    },
+
+    /// The query component, or the query parameters of a template, are
+    /// malformed. Only returned by the strict `parse` constructors.
+    #[error("invalid query `{query}`: {reason}")]
+    InvalidQueryComponent {
+        /// The offending query component.
+        query: String,
+        /// Why the query component is invalid.
+        reason: SyntaxError,
+    },
+
+    /// The same parameter name is declared more than once in a template,
+    /// either in its path or its query parameters.
+    #[error("parameters are declared more than once: {}", .0.join(", "))]
+    DuplicateParams(Vec<String>),
+
+    /// A catch-all parameter is not the final segment of a template.
+    #[error("catch-all parameter `{0}` needs to be the final segment")]
+    MisplacedCatchall(String),
+
+    /// A template has more than one catch-all parameter.
+    #[error("only one catch-all parameter is allowed, found: {}", .0.join(", "))]
+    MultipleCatchalls(Vec<String>),
+
+    /// An optional parameter is followed by a segment that is not an optional
+    /// parameter.
+    #[error("optional parameter `{0}` can only be followed by other optional parameters")]
+    MisplacedOptional(String),
+
+    /// When generating a populated route, the value of a parameter did not
+    /// satisfy its constraint.
+    #[error("value `{value}` of parameter `{param}` does not satisfy constraint `{constraint}`")]
+    UnsatisfiedConstraint {
+        /// The name of the parameter.
+        param: String,
+        /// The offending value.
+        value: String,
+        /// The constraint, as it is declared in the template.
+        constraint: String,
+    },
+
+    /// A segment of a template cannot be rendered in the syntax of a
+    /// [`Dialect`].
+    #[error("segment `{segment}` cannot be rendered for {dialect:?}: {reason}")]
+    UnsupportedByDialect {
+        /// The dialect the template was being rendered in.
+        dialect: Dialect,
+        /// The offending segment, in its "templated" representation.
+        segment: String,
+        /// Why the segment cannot be rendered.
+        reason: DialectError,
+    },
+
+    /// A segment of untrusted input could be used to traverse outside of the
+    /// route it was resolved against. Only returned by the `untrusted`
+    /// constructors.
+    #[error("unsafe segment `{segment}` at position {position}: {reason}")]
+    PathTraversal {
+        /// The position of the segment in the input, ignoring any empty
+        /// segments.
+        position: usize,
+        /// The offending segment.
+        segment: String,
+        /// Why the segment is unsafe.
+        reason: TraversalError,
+    },
+
+    /// An absolute URL is malformed.
+    #[error("invalid URL `{url}`: {reason}")]
+    InvalidUrl {
+        /// The offending URL.
+        url: String,
+        /// Why the URL is invalid.
+        reason: UrlError,
+    },
+
+    /// A route could not be converted into an `http::Uri` or
+    /// `http::uri::PathAndQuery` (e.g. because it is too long). Only returned
+    /// with the `http` feature.
+    #[error("unable to convert into a URI: {0}")]
+    InvalidUri(String),
+
+    /// No route is registered under the name in a
+    /// [`RouteRegistry`](crate::RouteRegistry).
+    #[error("no route is registered under the name `{0}`")]
+    UnknownRouteName(String),
+
+    /// A route is already registered under the name in a
+    /// [`RouteRegistry`](crate::RouteRegistry).
+    #[error("a route is already registered under the name `{0}`")]
+    DuplicateRouteName(String),
+
+    /// A route conflicts with one that was already added to a collection of
+    /// routes.
+    #[error(transparent)]
+    Conflict(#[from] RouteConflict),
+}
+
+impl WebRouteError {
+    pub(crate) fn invalid_segment(position: usize, segment: &str, reason: SyntaxError) -> Self {
+        Self::InvalidSegment {
+            position,
+            segment: segment.to_owned(),
+            reason,
+        }
+    }
+
+    pub(crate) fn path_traversal(position: usize, segment: &str, reason: TraversalError) -> Self {
+        Self::PathTraversal {
+            position,
+            segment: segment.to_owned(),
+            reason,
+        }
+    }
+
+    pub(crate) fn invalid_query_component(query: &str, reason: SyntaxError) -> Self {
+        Self::InvalidQueryComponent {
+            query: query.to_owned(),
+            reason,
+        }
+    }
+}
+
+/// Lists the missing and unused parameters of
+/// [`WebRouteError::MismatchedParams`].
+fn describe_mismatch(missing: &[String], unused: &[String]) -> String {
+    let mut descriptions = Vec::new();
+    if !missing.is_empty() {
+        descriptions.push(format!("no values for parameters: {}", missing.join(", ")));
+    }
+    if !unused.is_empty() {
+        descriptions.push(format!("unused values: {}", unused.join(", ")));
+    }
+
+    descriptions.join("; ")
+}
+
+/// Describes why part of a route is malformed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SyntaxError {
+    /// The segment is empty.
+    #[error("segment is empty")]
+    EmptySegment,
+
+    /// A `{` or `}` does not delimit a parameter.
+    #[error("unbalanced braces, a parameter needs to be enclosed in `{{` and `}}`")]
+    UnbalancedBraces,
+
+    /// Two parameters follow each other within a segment without any static
+    /// text between them (e.g. `{a}{b}`), so where one ends is ambiguous.
+    #[error("parameters need to be separated by static text within a segment")]
+    AdjacentParams,
+
+    /// A catch-all parameter shares its segment with static text or other
+    /// parameters (e.g. `{*path}.json`).
+    #[error("a catch-all parameter needs to span the whole segment")]
+    PartialCatchall,
+
+    /// A parameter has no name (e.g. `{}` or `{*}`).
+    #[error("parameter name is empty")]
+    EmptyParamName,
+
+    /// A parameter name contains something other than ASCII alphanumerics,
+    /// `_` and `.`, or has an empty component between its `.`s.
+    #[error("invalid character `{}` in parameter name", .0.escape_default())]
+    InvalidParamName(char),
+
+    /// Whitespace, control characters and characters with a special meaning
+    /// in a route (e.g. `?` or `#`) are not allowed.
+    #[error("invalid character `{}`", .0.escape_default())]
+    InvalidCharacter(char),
+
+    /// A `%` is not followed by two hex digits.
+    #[error("`%` needs to be followed by two hex digits")]
+    InvalidPercentEncoding,
+
+    /// A parameter constraint is neither one of the built-in constraints, nor
+    /// a valid regex (custom patterns require the `regex` feature, and cannot
+    /// be a bare word so that misspelled built-in constraints are caught).
+    #[error(
+        "unknown constraint `{0}`, expected `int`, `uuid`, `slug` or (with the `regex` feature) a valid regex that is not a bare word"
+    )]
+    InvalidConstraint(String),
+
+    /// The query parameters of a template (`{?...`) are not closed by a `}` at
+    /// the end of the template.
+    #[error("query parameters need to be closed by a `}}` at the end of the template")]
+    UnclosedQueryParams,
+}
+
+/// Describes why a segment of a template cannot be rendered in the syntax of
+/// a [`Dialect`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DialectError {
+    /// The segment has a parameter with a dotted name (e.g. `user.id`). Dotted
+    /// names only address nested values when populating a route, as webserver
+    /// frameworks cannot extract them into nested values.
+    #[error("dotted parameter `{0}` can only be used to populate a route")]
+    DottedParam(String),
+
+    /// The segment mixes static text and parameters (e.g. `{name}.{ext}`),
+    /// which the dialect has no syntax for.
+    #[error("segments that mix static text and parameters are not supported")]
+    MixedSegment,
+}
+
+/// Describes why a segment of untrusted input is unsafe.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TraversalError {
+    /// A `..` segment would resolve to above the base route.
+    #[error("`..` would escape above the base route")]
+    EscapesBase,
+
+    /// The segment is a percent-encoded `.` or `..` (e.g. `%2e%2e`), which
+    /// could be resolved as a dot-segment after being decoded.
+    #[error("percent-encoded dot-segment")]
+    EncodedDotSegment,
+
+    /// The segment contains a `\`, which some servers treat as a path
+    /// separator.
+    #[error("`\\`, which may be treated as a path separator")]
+    Backslash,
+
+    /// The segment contains a percent-encoded `/` or `\` (e.g. `%2F`), which
+    /// could be treated as a path separator after being decoded.
+    #[error("percent-encoded path separator")]
+    EncodedSeparator,
+
+    /// The segment contains a percent-encoded null byte (`%00`).
+    #[error("percent-encoded null byte")]
+    NullByte,
+}
+
+/// Describes why the origin or fragment of an absolute URL is malformed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UrlError {
+    /// The URL does not start with a scheme followed by `://`.
+    #[error("missing scheme, the URL needs to start with e.g. `https://`")]
+    MissingScheme,
+
+    /// The scheme does not start with a letter followed by letters, digits,
+    /// `+`, `-` or `.`.
+    #[error("invalid scheme")]
+    InvalidScheme,
+
+    /// The URL has no host.
+    #[error("host is empty")]
+    EmptyHost,
+
+    /// The authority contains whitespace, control characters, user
+    /// information (`@`) or other characters that are not allowed in it.
+    #[error("invalid character `{}` in authority", .0.escape_default())]
+    InvalidCharacter(char),
+
+    /// The fragment is malformed.
+    #[error("invalid fragment: {0}")]
+    InvalidFragment(SyntaxError),
 }
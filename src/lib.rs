@@ -2,11 +2,30 @@
 //! [`ParameterizedRoute`]: ParameterizedRoute
 #![doc = include_str!("../README.md")]
 
+pub mod display;
 pub mod error;
 pub mod parameterized_route;
+pub mod route_matcher;
+pub mod route_params;
+pub mod route_registry;
+pub mod route_segment;
+pub mod route_set;
 mod to_segments;
 mod utils;
 pub mod web_route;
+pub mod web_url;
 
 pub use parameterized_route::route::ParameterizedRoute;
+pub use route_matcher::RouteMatcher;
+pub use route_params::RouteParams;
+pub use route_registry::RouteRegistry;
+pub use route_segment::ToRouteSegment;
+pub use route_set::RouteSet;
 pub use web_route::route::WebRoute;
+#[cfg(feature = "macros")]
+pub use web_route_macros::{RouteParams, WebRoutes, fixed_route, route};
+pub use web_url::WebUrl;
+
+/// Used by the code generated by `web-route-macros`. Not public API.
+#[doc(hidden)]
+pub mod __private;
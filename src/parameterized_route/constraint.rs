@@ -0,0 +1,337 @@
+use std::fmt;
+
+use crate::error::SyntaxError;
+
+/// A constraint on the values of a named parameter, declared after a `:` in
+/// its template (e.g. `{id:int}`).
+///
+/// Constraints are enforced when populating a
+/// [`ParameterizedRoute`](crate::ParameterizedRoute) and when matching routes
+/// against it.
+///
+/// | Template                  | Constraint              | Accepts                                   |
+/// | ------------------------- | ----------------------- | ----------------------------------------- |
+/// | `{page:int}`              | [`Constraint::Int`]     | An optionally negative integer            |
+/// | `{id:uuid}`               | [`Constraint::Uuid`]    | A UUID                                    |
+/// | `{post:slug}`             | [`Constraint::Slug`]    | Lowercase alphanumerics separated by `-`  |
+/// | `{code:[A-Z]{3}}`         | `Constraint::Pattern`   | Values fully matching the regex           |
+///
+/// Custom patterns require the `regex` feature, and cannot be a bare word
+/// (e.g. `{id:Int}` is rejected rather than matching only `Int`).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Constraint {
+    /// An optionally negative integer (e.g. `42` or `-1`).
+    Int,
+
+    /// A hyphenated UUID (e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`), which
+    /// is how `uuid::Uuid` is displayed.
+    Uuid,
+
+    /// Lowercase ASCII alphanumerics, optionally separated by single `-`s
+    /// (e.g. `hello-world`).
+    Slug,
+
+    /// A custom regex that the whole value needs to match.
+    #[cfg(feature = "regex")]
+    Pattern(Pattern),
+}
+
+/// A custom regex [`Constraint`].
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    regex: regex::Regex,
+}
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    /// Returns the regex as it was declared in the template.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+const INT_PATTERN: &str = "-?[0-9]+";
+const UUID_PATTERN: &str =
+    "[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}";
+const SLUG_PATTERN: &str = "[a-z0-9]+(?:-[a-z0-9]+)*";
+
+impl Constraint {
+    /// Parses the part of a parameter's template after the `:`.
+    ///
+    /// # Errors
+    ///
+    /// - [`SyntaxError::InvalidConstraint`] if `constraint` is not a built-in
+    ///   constraint and is not a valid regex (or the `regex` feature is
+    ///   disabled). Bare words (e.g. `Int`) are never treated as a regex, so
+    ///   that a misspelled built-in constraint is not silently accepted.
+    pub(crate) fn parse(constraint: &str) -> Result<Self, SyntaxError> {
+        match constraint {
+            "int" => Ok(Constraint::Int),
+            "uuid" => Ok(Constraint::Uuid),
+            "slug" => Ok(Constraint::Slug),
+            word if is_bare_word(word) => Err(SyntaxError::InvalidConstraint(word.to_owned())),
+            #[cfg(feature = "regex")]
+            pattern => regex::Regex::new(&format!("^(?:{pattern})$"))
+                .map(|regex| {
+                    Constraint::Pattern(Pattern {
+                        source: pattern.to_owned(),
+                        regex,
+                    })
+                })
+                .map_err(|_| SyntaxError::InvalidConstraint(pattern.to_owned())),
+            #[cfg(not(feature = "regex"))]
+            pattern => Err(SyntaxError::InvalidConstraint(pattern.to_owned())),
+        }
+    }
+
+    /// Leniently parses the part of a parameter's template after the `:`,
+    /// returning [`None`] if it is not a valid constraint.
+    pub(crate) fn parse_lenient(constraint: &str) -> Option<Self> {
+        Self::parse(constraint).ok()
+    }
+
+    /// Converts a regex constrained parameter of a framework into a
+    /// [`Constraint`], recognizing the regexes of the built-in constraints.
+    pub(crate) fn from_regex(pattern: &str) -> Option<Self> {
+        match pattern {
+            INT_PATTERN => Some(Constraint::Int),
+            UUID_PATTERN => Some(Constraint::Uuid),
+            SLUG_PATTERN => Some(Constraint::Slug),
+            #[cfg(feature = "regex")]
+            pattern => Self::parse_lenient(pattern),
+            #[cfg(not(feature = "regex"))]
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the (decoded) `value` satisfies the constraint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::parameterized_route::constraint::Constraint;
+    ///
+    /// assert!(Constraint::Int.is_satisfied_by("-42"));
+    /// assert!(!Constraint::Int.is_satisfied_by("me"));
+    /// assert!(Constraint::Slug.is_satisfied_by("hello-world"));
+    /// assert!(!Constraint::Slug.is_satisfied_by("Hello World"));
+    /// ```
+    pub fn is_satisfied_by(&self, value: &str) -> bool {
+        match self {
+            Constraint::Int => {
+                let digits = value.strip_prefix('-').unwrap_or(value);
+                !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+            }
+            Constraint::Uuid => is_uuid(value),
+            Constraint::Slug => {
+                !value.is_empty()
+                    && value
+                        .split('-')
+                        .all(|part| !part.is_empty() && part.bytes().all(is_slug_byte))
+            }
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(pattern) => pattern.regex.is_match(value),
+        }
+    }
+
+    /// Returns the constraint as it is declared in a template (e.g. `int`).
+    pub fn as_str(&self) -> &str {
+        match self {
+            Constraint::Int => "int",
+            Constraint::Uuid => "uuid",
+            Constraint::Slug => "slug",
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(pattern) => pattern.as_str(),
+        }
+    }
+
+    /// Returns a regex that matches the same values as the constraint, for
+    /// frameworks that support regex constrained parameters.
+    pub(crate) fn to_regex(&self) -> &str {
+        match self {
+            Constraint::Int => INT_PATTERN,
+            Constraint::Uuid => UUID_PATTERN,
+            Constraint::Slug => SLUG_PATTERN,
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(pattern) => pattern.as_str(),
+        }
+    }
+}
+
+/// Returns `true` if `constraint` is made up of only ASCII alphanumerics and
+/// `_`, i.e. it looks like the name of a built-in constraint.
+fn is_bare_word(constraint: &str) -> bool {
+    constraint
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+fn is_slug_byte(b: u8) -> bool {
+    b.is_ascii_lowercase() || b.is_ascii_digit()
+}
+
+/// Returns `true` if `value` is a hyphenated UUID, matching
+/// [`UUID_PATTERN`] regardless of the `uuid` feature.
+fn is_uuid(value: &str) -> bool {
+    let groups = value.split('-').collect::<Vec<_>>();
+
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Constraints are equal if they are declared the same way.
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Constraint {}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(feature = "fake")]
+impl fake::Dummy<fake::Faker> for Constraint {
+    fn dummy_with_rng<R: fake::Rng + ?Sized>(_: &fake::Faker, rng: &mut R) -> Self {
+        match rng.random_range(0..3) {
+            0 => Constraint::Int,
+            1 => Constraint::Uuid,
+            _ => Constraint::Slug,
+        }
+    }
+}
+
+#[cfg(test)]
+mod constraint_tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_built_in_constraints() {
+            // Act
+            let int = Constraint::parse("int");
+            let uuid = Constraint::parse("uuid");
+            let slug = Constraint::parse("slug");
+
+            // Assert
+            assert_eq!(int, Ok(Constraint::Int));
+            assert_eq!(uuid, Ok(Constraint::Uuid));
+            assert_eq!(slug, Ok(Constraint::Slug));
+        }
+
+        #[cfg(feature = "regex")]
+        #[test]
+        fn should_parse_pattern() {
+            // Act
+            let constraint = Constraint::parse("[A-Z]{3}").unwrap();
+
+            // Assert
+            assert_eq!(constraint.as_str(), "[A-Z]{3}");
+            assert!(constraint.is_satisfied_by("ABC"));
+            assert!(
+                !constraint.is_satisfied_by("ABCD"),
+                "should match the whole value"
+            );
+        }
+
+        #[test]
+        fn should_reject_unknown_bare_words() {
+            for constraint in ["Int", "integer", "u32", "my_constraint"] {
+                // Act
+                let res = Constraint::parse(constraint);
+
+                // Assert
+                assert_eq!(
+                    res,
+                    Err(SyntaxError::InvalidConstraint(constraint.to_owned())),
+                    "{constraint:?}"
+                );
+            }
+        }
+
+        #[cfg(feature = "regex")]
+        #[test]
+        fn should_reject_invalid_pattern() {
+            // Act
+            let res = Constraint::parse("[A-Z");
+
+            // Assert
+            assert_eq!(res, Err(SyntaxError::InvalidConstraint("[A-Z".to_owned())));
+        }
+
+        #[cfg(not(feature = "regex"))]
+        #[test]
+        fn should_reject_pattern_without_regex_feature() {
+            // Act
+            let res = Constraint::parse("[A-Z]{3}");
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    mod is_satisfied_by {
+        use super::*;
+
+        #[test]
+        fn should_check_int() {
+            for (value, expected) in [
+                ("0", true),
+                ("-12", true),
+                ("", false),
+                ("-", false),
+                ("1.5", false),
+            ] {
+                // Act
+                let satisfied = Constraint::Int.is_satisfied_by(value);
+
+                // Assert
+                assert_eq!(satisfied, expected, "{value:?}");
+            }
+        }
+
+        #[test]
+        fn should_check_uuid() {
+            // Act
+            let valid = Constraint::Uuid.is_satisfied_by("67e55044-10b1-426f-9247-bb680e5fe0c8");
+            let invalid = Constraint::Uuid.is_satisfied_by("67e55044-10b1-426f-9247");
+            let simple = Constraint::Uuid.is_satisfied_by("67e5504410b1426f9247bb680e5fe0c8");
+            let braced = Constraint::Uuid.is_satisfied_by("{67e55044-10b1-426f-9247-bb680e5fe0c8}");
+
+            // Assert
+            assert!(valid);
+            assert!(!invalid);
+            assert!(!simple, "should only accept the hyphenated format");
+            assert!(!braced, "should only accept the hyphenated format");
+        }
+
+        #[test]
+        fn should_check_slug() {
+            for (value, expected) in [
+                ("hello-world-2", true),
+                ("-hello", false),
+                ("hello--world", false),
+                ("Hello", false),
+                ("", false),
+            ] {
+                // Act
+                let satisfied = Constraint::Slug.is_satisfied_by(value);
+
+                // Assert
+                assert_eq!(satisfied, expected, "{value:?}");
+            }
+        }
+    }
+}
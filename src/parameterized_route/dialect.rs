@@ -0,0 +1,591 @@
+use crate::{
+    error::DialectError,
+    parameterized_route::{
+        constraint::Constraint,
+        segment::{ParameterizedSegment, SegmentPart},
+    },
+};
+
+/// The route template syntax of a particular web framework.
+///
+/// Allows a single [`ParameterizedRoute`](crate::ParameterizedRoute) to be
+/// registered with (and parsed from) any of the supported frameworks.
+///
+/// | Dialect                | Named parameter | Constrained parameter | Catch-all parameter |
+/// | ---------------------- | --------------- | --------------------- | ------------------- |
+/// | [`Dialect::Axum`]      | `{name}`        | `{name}`              | `{*name}`           |
+/// | [`Dialect::ActixWeb`]  | `{name}`        | `{name:regex}`        | `{name:.*}`         |
+/// | [`Dialect::Rocket`]    | `<name>`        | `<name>`              | `<name..>`          |
+/// | [`Dialect::Express`]   | `:name`         | `:name`               | `*name`             |
+///
+/// Segments that mix static text and parameters (e.g. `{name}.{ext}`) use the
+/// named parameter syntax for each of their parameters, except for
+/// [`Dialect::Rocket`] which only allows a parameter to span a whole segment.
+///
+/// Parameter [`Constraint`]s are translated into a regex for
+/// [`Dialect::ActixWeb`], and are stripped for the others (`express` 5 and
+/// `axum` 0.7 reject the `:name(regex)` syntax of `express` 4, which is still
+/// parsed). Likewise, only
+/// [`Dialect::Express`] supports optional parameters (`:name?`), the others
+/// render them as required parameters. Use
+/// [`ParameterizedRoute::expand_optional`](crate::ParameterizedRoute::expand_optional)
+/// to register each form of the route with those frameworks.
+///
+/// Dotted parameter names (e.g. `{user.id}`) only address nested values when
+/// populating a route, and cannot be rendered in any dialect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dialect {
+    /// The syntax used by `axum` 0.8 (and `matchit` 0.8). This is the syntax
+    /// used by [`ParameterizedRoute::new`](crate::ParameterizedRoute::new).
+    #[default]
+    Axum,
+
+    /// The syntax used by `actix-web`.
+    ActixWeb,
+
+    /// The syntax used by `rocket`.
+    Rocket,
+
+    /// The syntax used by `express`, and `axum` prior to 0.8.
+    Express,
+}
+
+impl Dialect {
+    /// Checks that `segment` can be rendered in the dialect's syntax.
+    ///
+    /// # Errors
+    ///
+    /// - [`DialectError::DottedParam`] if a parameter of `segment` has a
+    ///   dotted name.
+    /// - [`DialectError::MixedSegment`] if `segment` mixes static text and
+    ///   parameters, and the dialect is [`Dialect::Rocket`].
+    pub(crate) fn check_segment(self, segment: &ParameterizedSegment) -> Result<(), DialectError> {
+        if let Some(param) = segment
+            .params()
+            .into_iter()
+            .find(|param| param.contains('.'))
+        {
+            return Err(DialectError::DottedParam(param.to_owned()));
+        }
+
+        if self == Dialect::Rocket && matches!(segment, ParameterizedSegment::Mixed(_)) {
+            return Err(DialectError::MixedSegment);
+        }
+
+        Ok(())
+    }
+
+    /// Renders a named parameter in the dialect's syntax.
+    pub(crate) fn named_param(
+        self,
+        name: &str,
+        constraint: Option<&Constraint>,
+        optional: bool,
+    ) -> String {
+        if self == Dialect::Express && optional {
+            return format!("{}?", self.named_param(name, constraint, false));
+        }
+
+        match (self, constraint) {
+            (Dialect::ActixWeb, Some(constraint)) => {
+                format!("{{{name}:{}}}", constraint.to_regex())
+            }
+            (Dialect::Axum | Dialect::ActixWeb, _) => format!("{{{name}}}"),
+            (Dialect::Rocket, _) => format!("<{name}>"),
+            (Dialect::Express, _) => format!(":{name}"),
+        }
+    }
+
+    /// Renders a catch-all parameter in the dialect's syntax.
+    pub(crate) fn catchall_param(self, name: &str) -> String {
+        match self {
+            Dialect::Axum => format!("{{*{name}}}"),
+            Dialect::ActixWeb => format!("{{{name}:.*}}"),
+            Dialect::Rocket => format!("<{name}..>"),
+            Dialect::Express => format!("*{name}"),
+        }
+    }
+
+    /// Attempts to parse `segment` as a parameter in the dialect's syntax, or
+    /// as a mix of static text and parameters (e.g. `{name}.{ext}`).
+    ///
+    /// Returns [`None`] if `segment` has no parameters. Constraints that are
+    /// not understood are dropped.
+    pub(crate) fn parse_param(self, segment: &str) -> Option<ParameterizedSegment> {
+        if self == Dialect::Express
+            && let Some(catchall_param) = segment.strip_prefix('*')
+        {
+            return Some(ParameterizedSegment::CatchallParam(
+                catchall_param.to_owned(),
+            ));
+        }
+
+        match self.split_parts(segment)?.as_slice() {
+            [TemplatePart::Param(param)] => Some(self.parse_whole_param(param)),
+            parts
+                if parts
+                    .iter()
+                    .any(|part| matches!(part, TemplatePart::Param(_))) =>
+            {
+                Some(ParameterizedSegment::Mixed(
+                    parts
+                        .iter()
+                        .map(|part| match part {
+                            TemplatePart::Static(value) => SegmentPart::Static((*value).to_owned()),
+                            TemplatePart::Param(param) => self.parse_partial_param(param),
+                        })
+                        .collect(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Splits `segment` into its static text and the contents of its
+    /// parameters in the dialect's syntax.
+    ///
+    /// Returns [`None`] if the parameter delimiters are unbalanced.
+    fn split_parts(self, segment: &str) -> Option<Vec<TemplatePart<'_>>> {
+        match self {
+            Dialect::Axum | Dialect::ActixWeb => split_delimited(segment, '{', '}'),
+            Dialect::Rocket => split_delimited(segment, '<', '>'),
+            Dialect::Express => Some(split_express(segment)),
+        }
+    }
+
+    /// Parses the contents of a parameter that spans a whole segment.
+    fn parse_whole_param(self, param: &str) -> ParameterizedSegment {
+        match self {
+            Dialect::Axum => match param.strip_prefix('*') {
+                Some(catchall_param) => {
+                    ParameterizedSegment::CatchallParam(catchall_param.to_owned())
+                }
+                None => match param.split_once(':') {
+                    Some((name, constraint)) => {
+                        named_param(name, Constraint::parse_lenient(constraint))
+                    }
+                    None => named_param(param, None),
+                },
+            },
+            Dialect::ActixWeb => match param.split_once(':') {
+                Some((name, ".*" | ".+")) => ParameterizedSegment::CatchallParam(name.to_owned()),
+                Some((name, pattern)) => named_param(name, Constraint::from_regex(pattern)),
+                None => named_param(param, None),
+            },
+            Dialect::Rocket => match param.strip_suffix("..") {
+                Some(catchall_param) => {
+                    ParameterizedSegment::CatchallParam(catchall_param.to_owned())
+                }
+                None => named_param(param, None),
+            },
+            Dialect::Express => {
+                // The optional marker follows any pattern (`:name(regex)?`), so
+                // it is moved onto the name.
+                let (param, optional) = match param.strip_suffix('?') {
+                    Some(param) => (param, "?"),
+                    None => (param, ""),
+                };
+
+                match param
+                    .strip_suffix(')')
+                    .and_then(|param| param.split_once('('))
+                {
+                    Some((name, pattern)) => named_param(
+                        &format!("{name}{optional}"),
+                        Constraint::from_regex(pattern),
+                    ),
+                    None => named_param(&format!("{param}{optional}"), None),
+                }
+            }
+        }
+    }
+
+    /// Parses the contents of a parameter that is part of a mixed segment.
+    fn parse_partial_param(self, param: &str) -> SegmentPart {
+        let (name, constraint) = match self {
+            Dialect::Axum => match param.split_once(':') {
+                Some((name, constraint)) => (name, Constraint::parse_lenient(constraint)),
+                None => (param, None),
+            },
+            Dialect::ActixWeb => match param.split_once(':') {
+                Some((name, pattern)) => (name, Constraint::from_regex(pattern)),
+                None => (param, None),
+            },
+            Dialect::Rocket => (param, None),
+            Dialect::Express => match param
+                .strip_suffix(')')
+                .and_then(|param| param.split_once('('))
+            {
+                Some((name, pattern)) => (name, Constraint::from_regex(pattern)),
+                None => (param, None),
+            },
+        };
+
+        SegmentPart::Param {
+            name: name.to_owned(),
+            constraint,
+        }
+    }
+}
+
+/// A part of a segment in the syntax of a [`Dialect`], before its parameters
+/// have been parsed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TemplatePart<'a> {
+    /// Static text.
+    Static(&'a str),
+    /// The contents of a parameter, without its delimiters.
+    Param(&'a str),
+}
+
+/// Splits `segment` into static text and the contents of the parameters
+/// delimited by `open` and `close`. Delimiters nested within a parameter (e.g.
+/// the `{3}` of `{id:[0-9]{3}}`) are kept as part of it.
+///
+/// Returns [`None`] if the delimiters are unbalanced.
+pub(crate) fn split_delimited(
+    segment: &str,
+    open: char,
+    close: char,
+) -> Option<Vec<TemplatePart<'_>>> {
+    let mut parts = Vec::new();
+    let mut remaining = segment;
+
+    while let Some(start) = remaining.find([open, close]) {
+        if remaining[start..].starts_with(close) {
+            return None;
+        }
+
+        if start > 0 {
+            parts.push(TemplatePart::Static(&remaining[..start]));
+        }
+
+        let len = delimited_len(&remaining[start..], open, close)?;
+        parts.push(TemplatePart::Param(
+            &remaining[start + open.len_utf8()..start + len - close.len_utf8()],
+        ));
+        remaining = &remaining[start + len..];
+    }
+
+    if !remaining.is_empty() {
+        parts.push(TemplatePart::Static(remaining));
+    }
+
+    Some(parts)
+}
+
+/// Splits `segment` into static text and the parameters in the syntax of
+/// [`Dialect::Express`] (e.g. `:name.:ext`), without their leading `:`.
+fn split_express(segment: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut static_start = 0;
+    let mut position = 0;
+
+    while let Some(offset) = segment[position..].find(':') {
+        let param_start = position + offset + 1;
+        let name_end = segment[param_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .map_or(segment.len(), |len| param_start + len);
+
+        // A `:` that is not followed by a name is static text.
+        if name_end == param_start {
+            position = param_start;
+            continue;
+        }
+
+        let mut param_end = name_end;
+        if let Some(len) = delimited_len(&segment[param_end..], '(', ')') {
+            param_end += len;
+        }
+        // Only a parameter at the end of the segment can be optional.
+        if &segment[param_end..] == "?" {
+            param_end += 1;
+        }
+
+        if static_start < param_start - 1 {
+            parts.push(TemplatePart::Static(
+                &segment[static_start..param_start - 1],
+            ));
+        }
+        parts.push(TemplatePart::Param(&segment[param_start..param_end]));
+        static_start = param_end;
+        position = param_end;
+    }
+
+    if static_start < segment.len() {
+        parts.push(TemplatePart::Static(&segment[static_start..]));
+    }
+
+    parts
+}
+
+/// Returns the length of the delimited section at the start of `value`,
+/// including any nested delimiters, or [`None`] if it does not start with
+/// `open` or is never closed.
+fn delimited_len(value: &str, open: char, close: char) -> Option<usize> {
+    if !value.starts_with(open) {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (index, c) in value.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+
+            if depth == 0 {
+                return Some(index + c.len_utf8());
+            }
+        }
+    }
+
+    None
+}
+
+/// Builds a named parameter, which is optional if `name` ends with `?`.
+fn named_param(name: &str, constraint: Option<Constraint>) -> ParameterizedSegment {
+    let (name, optional) = match name.strip_suffix('?') {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+
+    ParameterizedSegment::NamedParam {
+        name: name.to_owned(),
+        constraint,
+        optional,
+    }
+}
+
+#[cfg(test)]
+mod dialect_tests {
+    use super::*;
+
+    mod parse_param {
+        use super::*;
+
+        #[test]
+        fn should_parse_each_dialects_named_parameter() {
+            for (dialect, segment) in [
+                (Dialect::Axum, "{id}"),
+                (Dialect::ActixWeb, "{id}"),
+                (Dialect::Rocket, "<id>"),
+                (Dialect::Express, ":id"),
+            ] {
+                // Act
+                let param = dialect.parse_param(segment);
+
+                // Assert
+                assert_eq!(
+                    param,
+                    Some(named_param("id", None)),
+                    "{dialect:?} should parse {segment}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_parse_each_dialects_catchall_parameter() {
+            for (dialect, segment) in [
+                (Dialect::Axum, "{*rest}"),
+                (Dialect::ActixWeb, "{rest:.*}"),
+                (Dialect::Rocket, "<rest..>"),
+                (Dialect::Express, "*rest"),
+            ] {
+                // Act
+                let param = dialect.parse_param(segment);
+
+                // Assert
+                assert_eq!(
+                    param,
+                    Some(ParameterizedSegment::CatchallParam("rest".to_owned())),
+                    "{dialect:?} should parse {segment}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_not_parse_static_segment() {
+            for dialect in [
+                Dialect::Axum,
+                Dialect::ActixWeb,
+                Dialect::Rocket,
+                Dialect::Express,
+            ] {
+                // Act
+                let param = dialect.parse_param("static");
+
+                // Assert
+                assert_eq!(param, None, "{dialect:?} should not parse a static segment");
+            }
+        }
+
+        #[test]
+        fn should_translate_constraints() {
+            // Act
+            let param = Dialect::ActixWeb.parse_param("{id:-?[0-9]+}");
+
+            // Assert
+            assert_eq!(param, Some(named_param("id", Some(Constraint::Int))));
+            assert_eq!(
+                param.unwrap().to_template_with(Dialect::ActixWeb),
+                "{id:-?[0-9]+}"
+            );
+        }
+
+        #[test]
+        fn should_parse_express_4_constraints() {
+            // Act
+            let param = Dialect::Express.parse_param(":id(-?[0-9]+)");
+
+            // Assert
+            assert_eq!(param, Some(named_param("id", Some(Constraint::Int))));
+        }
+
+        #[test]
+        fn should_parse_axum_dialect_constraint() {
+            // Act
+            let param = Dialect::Axum.parse_param("{id:int}");
+
+            // Assert
+            assert_eq!(param, Some(named_param("id", Some(Constraint::Int))));
+        }
+
+        #[test]
+        fn should_strip_constraints_for_dialects_without_them() {
+            // Arrange
+            let param = named_param("id", Some(Constraint::Uuid));
+
+            // Act
+            let axum = param.to_template_with(Dialect::Axum);
+            let rocket = param.to_template_with(Dialect::Rocket);
+            let express = param.to_template_with(Dialect::Express);
+
+            // Assert
+            assert_eq!(axum, "{id}");
+            assert_eq!(rocket, "<id>");
+            assert_eq!(express, ":id");
+        }
+
+        #[test]
+        fn should_parse_optional_parameters() {
+            for (dialect, segment) in [(Dialect::Axum, "{year?}"), (Dialect::Express, ":year?")] {
+                // Act
+                let param = dialect.parse_param(segment);
+
+                // Assert
+                assert_eq!(
+                    param,
+                    Some(named_param("year?", None)),
+                    "{dialect:?} should parse {segment}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_render_optional_parameters_only_for_express() {
+            // Arrange
+            let param = named_param("year?", Some(Constraint::Int));
+
+            // Act
+            let express = param.to_template_with(Dialect::Express);
+            let actix = param.to_template_with(Dialect::ActixWeb);
+
+            // Assert
+            assert_eq!(express, ":year?");
+            assert_eq!(actix, "{year:-?[0-9]+}");
+            assert_eq!(
+                Dialect::Express.parse_param(&express),
+                Some(named_param("year?", None))
+            );
+        }
+
+        #[test]
+        fn should_round_trip_each_dialects_mixed_segment() {
+            // Arrange
+            let segment = ParameterizedSegment::Mixed(vec![
+                SegmentPart::Param {
+                    name: "name".to_owned(),
+                    constraint: None,
+                },
+                SegmentPart::Static(".".to_owned()),
+                SegmentPart::Param {
+                    name: "ext".to_owned(),
+                    constraint: None,
+                },
+            ]);
+
+            for (dialect, template) in [
+                (Dialect::Axum, "{name}.{ext}"),
+                (Dialect::ActixWeb, "{name}.{ext}"),
+                (Dialect::Express, ":name.:ext"),
+            ] {
+                // Act
+                let rendered = segment.to_template_with(dialect);
+                let parsed = dialect.parse_param(template);
+
+                // Assert
+                assert_eq!(rendered, template, "{dialect:?} should render {template}");
+                assert_eq!(
+                    parsed.as_ref(),
+                    Some(&segment),
+                    "{dialect:?} should parse {template}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_keep_nested_delimiters_within_param() {
+            // Act
+            let param = Dialect::ActixWeb.parse_param("{id:-?[0-9]+}.json");
+
+            // Assert
+            assert_eq!(
+                param,
+                Some(ParameterizedSegment::Mixed(vec![
+                    SegmentPart::Param {
+                        name: "id".to_owned(),
+                        constraint: Some(Constraint::Int),
+                    },
+                    SegmentPart::Static(".json".to_owned()),
+                ]))
+            );
+            assert_eq!(
+                split_delimited("{id:[0-9]{3}}", '{', '}'),
+                Some(vec![TemplatePart::Param("id:[0-9]{3}")])
+            );
+        }
+
+        #[test]
+        fn should_not_parse_unbalanced_delimiters() {
+            // Act
+            let param = Dialect::Axum.parse_param("{id}}");
+
+            // Assert
+            assert_eq!(param, None);
+        }
+
+        #[cfg(not(feature = "regex"))]
+        #[test]
+        fn should_keep_only_name_of_unsupported_custom_pattern() {
+            // Act
+            let param = Dialect::ActixWeb.parse_param(r"{id:\d+}");
+
+            // Assert
+            assert_eq!(param, Some(named_param("id", None)));
+        }
+
+        #[cfg(feature = "regex")]
+        #[test]
+        fn should_keep_custom_pattern() {
+            // Act
+            let param = Dialect::ActixWeb.parse_param(r"{id:\d+}").unwrap();
+
+            // Assert
+            assert_eq!(
+                param.constraint().map(|constraint| constraint.as_str()),
+                Some(r"\d+")
+            );
+        }
+    }
+}
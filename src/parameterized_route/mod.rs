@@ -1,2 +1,4 @@
+pub mod constraint;
+pub mod dialect;
 pub mod route;
-pub(crate) mod segment;
+pub mod segment;
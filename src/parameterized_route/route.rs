@@ -1,8 +1,20 @@
-use std::{fmt, ops};
+use std::{borrow::Cow, collections::HashMap, fmt, ops};
+
+use serde::de::DeserializeOwned;
 
 use crate::{
-    WebRoute, error::WebRouteError, parameterized_route::segment::ParameterizedSegment,
-    to_segments::ToParameterizedSegments, utils::struct_to_map,
+    WebRoute,
+    error::{SyntaxError, WebRouteError},
+    parameterized_route::{
+        dialect::Dialect,
+        segment::{ParameterizedSegment, SegmentPart, match_parts},
+    },
+    route_params::RouteParams,
+    to_segments::{ToFixedSegments, ToParameterizedSegments},
+    utils::{
+        declared_query_pairs, map_to_struct, split_query_template, split_segments,
+        validate_param_name,
+    },
 };
 
 /// Defines a route structure that can be used to define routes for a webserver.
@@ -10,28 +22,100 @@ use crate::{
 /// Its templated sections can be easily populated to create a [`WebRoute`]
 /// which can be used to make requests against the webserver routes that the
 /// [`ParameterizedRoute`] was used to define.
+///
+/// A route can also declare query parameters (e.g. `/items{?page,per_page}`).
+/// These are not part of the path template that is used to register the route
+/// with a webserver (see [`Deref`](ops::Deref)), but are populated as a query
+/// by [`ParameterizedRoute::to_web_route`].
+///
+/// Trailing parameters can be optional (e.g. `/reports/{year?}`), in which
+/// case they are omitted when they have no value. The route derefs to its
+/// longest form, see [`ParameterizedRoute::expand_optional`] for registering
+/// every form with a webserver.
 #[derive(Clone, PartialEq)]
-pub struct ParameterizedRoute(String);
+pub struct ParameterizedRoute {
+    /// The normalized template, including any constraints and query
+    /// parameters.
+    pub(crate) template: Cow<'static, str>,
+
+    /// The path template in the syntax of [`Dialect::Axum`], which is what
+    /// the route derefs to.
+    pub(crate) path: Cow<'static, str>,
+}
 
 impl ParameterizedRoute {
     /// Creates a new [`ParameterizedRoute`].
     ///
+    /// Malformed input is worked around rather than rejected. In particular,
+    /// a constraint that cannot be parsed (e.g. the misspelled `{id:Int}`) is
+    /// dropped, leaving the parameter unconstrained. Use
+    /// [`ParameterizedRoute::parse`] to reject it instead.
+    ///
     /// # Examples
     ///
     /// ```
     /// use web_route::ParameterizedRoute;
     ///
     /// let route = ParameterizedRoute::new("/some/route/{param}");
+    /// let route_with_query = ParameterizedRoute::new("/some/route{?page,per_page}");
     /// ```
     pub fn new<R: ToParameterizedSegments>(route: R) -> Self {
         let segments = route.to_segments();
 
-        Self(evaluate_segments(segments))
+        Self::from_template(evaluate_segments(segments, route.to_query_params()))
+    }
+
+    /// Strictly parses a new [`ParameterizedRoute`].
+    ///
+    /// Unlike [`ParameterizedRoute::new`], which works around malformed input,
+    /// any malformed segment or query parameter is rejected. Repeated, leading
+    /// and trailing slashes are still normalized.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::InvalidSegment`] if a segment has unbalanced braces,
+    ///   an empty or invalid parameter name, contains whitespace, control
+    ///   characters, `?` or `#`, or is not correctly percent-encoded.
+    /// - [`WebRouteError::InvalidQueryComponent`] if the query parameters are
+    ///   not closed by a `}` at the end of the template, or any of their names
+    ///   are invalid.
+    /// - Any of the errors of [`ParameterizedRoute::validate`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, error::WebRouteError};
+    ///
+    /// let route = ParameterizedRoute::parse("/foo/{foo_id}{?page}").unwrap();
+    /// assert_eq!(route, ParameterizedRoute::new("/foo/{foo_id}{?page}"));
+    ///
+    /// let res = ParameterizedRoute::parse("/foo/{bar/baz}");
+    /// assert!(matches!(res, Err(WebRouteError::InvalidSegment { position: 1, .. })));
+    /// ```
+    pub fn parse(route: &str) -> Result<Self, WebRouteError> {
+        let (path, query_params) = match route.split_once("{?") {
+            Some((path, query)) => (path, parse_query_params(query)?),
+            None => (route, Vec::new()),
+        };
+
+        let segments = split_segments(path)
+            .map(|(position, segment)| {
+                ParameterizedSegment::parse(segment)
+                    .map_err(|reason| WebRouteError::invalid_segment(position, segment, reason))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let route = Self::from_template(evaluate_segments(segments, query_params));
+        route.validate()?;
+
+        Ok(route)
     }
 
     /// Joins a route onto an existing [`ParameterizedRoute`] returning the
     /// joined route.
     ///
+    /// The query parameters of both routes are combined.
+    ///
     /// # Examples
     ///
     /// ```
@@ -46,22 +130,304 @@ impl ParameterizedRoute {
     pub fn join<R: ToParameterizedSegments>(&self, route: R) -> Self {
         let joined_segments = [self.to_segments(), route.to_segments()].concat();
 
-        Self(evaluate_segments(joined_segments))
+        Self::from_template(evaluate_segments(
+            joined_segments,
+            self.query_params()
+                .into_iter()
+                .chain(route.to_query_params())
+                .collect(),
+        ))
+    }
+
+    /// Joins a route onto an existing [`ParameterizedRoute`] like
+    /// [`ParameterizedRoute::join`], but validates the joined route.
+    ///
+    /// This catches nested routes that reuse a parameter name, or that are
+    /// joined onto a route ending in a catch-all.
+    ///
+    /// # Errors
+    ///
+    /// Any of the errors of [`ParameterizedRoute::validate`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, error::WebRouteError};
+    ///
+    /// let route = ParameterizedRoute::new("/users/{id}");
+    ///
+    /// assert!(route.try_join("/posts/{post_id}").is_ok());
+    /// assert!(matches!(
+    ///     route.try_join("/posts/{id}"),
+    ///     Err(WebRouteError::DuplicateParams(params)) if params == vec!["id"]
+    /// ));
+    /// ```
+    pub fn try_join<R: ToParameterizedSegments>(&self, route: R) -> Result<Self, WebRouteError> {
+        let joined_route = self.join(route);
+        joined_route.validate()?;
+
+        Ok(joined_route)
+    }
+
+    /// Checks that the route could be registered with a webserver.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::MultipleCatchalls`] if there is more than one
+    ///   catch-all parameter.
+    /// - [`WebRouteError::MisplacedCatchall`] if a catch-all parameter is not
+    ///   the final segment.
+    /// - [`WebRouteError::MisplacedOptional`] if an optional parameter is
+    ///   followed by a segment that is not an optional parameter.
+    /// - [`WebRouteError::DuplicateParams`] if a parameter name is declared
+    ///   more than once, including in the query parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, error::WebRouteError};
+    ///
+    /// assert!(ParameterizedRoute::new("/{id}/x/{*rest}").validate().is_ok());
+    /// assert!(matches!(
+    ///     ParameterizedRoute::new("/{*rest}/more").validate(),
+    ///     Err(WebRouteError::MisplacedCatchall(param)) if param == "rest"
+    /// ));
+    /// ```
+    pub fn validate(&self) -> Result<(), WebRouteError> {
+        let segments = self.to_segments();
+
+        let catchall_params = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                ParameterizedSegment::CatchallParam(param) => Some(param.to_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if catchall_params.len() > 1 {
+            return Err(WebRouteError::MultipleCatchalls(catchall_params));
+        }
+
+        if let Some(param) = catchall_params.first()
+            && !matches!(
+                segments.last(),
+                Some(ParameterizedSegment::CatchallParam(_))
+            )
+        {
+            return Err(WebRouteError::MisplacedCatchall(param.to_owned()));
+        }
+
+        // Optional parameters need to form a trailing run, so that omitting
+        // them cannot leave a gap in the route.
+        if let Some(first_optional) = segments.iter().position(ParameterizedSegment::is_optional)
+            && !segments[first_optional..]
+                .iter()
+                .all(ParameterizedSegment::is_optional)
+        {
+            let param = segments[first_optional].param().unwrap_or_default();
+            return Err(WebRouteError::MisplacedOptional(param.to_owned()));
+        }
+
+        let mut seen_params = Vec::new();
+        let mut duplicate_params = Vec::new();
+        for param in segments
+            .iter()
+            .flat_map(ParameterizedSegment::params)
+            .map(ToOwned::to_owned)
+            .chain(self.query_params())
+        {
+            if !seen_params.contains(&param) {
+                seen_params.push(param);
+            } else if !duplicate_params.contains(&param) {
+                duplicate_params.push(param);
+            }
+        }
+
+        if !duplicate_params.is_empty() {
+            return Err(WebRouteError::DuplicateParams(duplicate_params));
+        }
+
+        Ok(())
+    }
+
+    /// Declares additional query parameters on the route, returning the
+    /// updated route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/items").with_query_params(["page", "per_page"]);
+    ///
+    /// assert_eq!(route, ParameterizedRoute::new("/items{?page,per_page}"));
+    /// assert_eq!(route.query_params(), vec!["page", "per_page"]);
+    /// ```
+    pub fn with_query_params<I, S>(&self, query_params: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::from_template(evaluate_segments(
+            self.to_segments(),
+            self.query_params()
+                .into_iter()
+                .chain(query_params.into_iter().map(Into::into))
+                .collect(),
+        ))
+    }
+
+    /// Returns the names of the route's query parameters.
+    pub fn query_params(&self) -> Vec<String> {
+        ToParameterizedSegments::to_query_params(&self.template.as_ref())
+    }
+
+    /// Creates a new [`ParameterizedRoute`] from a template written in the
+    /// syntax of a particular `dialect`.
+    ///
+    /// Any query parameters still need to be declared as `{?a,b}`, as none of
+    /// the dialects have a syntax for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, parameterized_route::dialect::Dialect};
+    ///
+    /// let route = ParameterizedRoute::from_template_with("/files/<user_id>/<path..>", Dialect::Rocket);
+    ///
+    /// assert_eq!(route, ParameterizedRoute::new("/files/{user_id}/{*path}"));
+    /// ```
+    pub fn from_template_with(template: &str, dialect: Dialect) -> Self {
+        let (path, query_params) = split_query_template(template);
+        let segments = path
+            .split('/')
+            .filter_map(|segment| ParameterizedSegment::from_template_with(segment, dialect).ok())
+            .collect();
+
+        Self::from_template(evaluate_segments(
+            segments,
+            query_params.into_iter().map(ToOwned::to_owned).collect(),
+        ))
+    }
+
+    /// Renders the path template of the route in the syntax of a particular
+    /// `dialect`, so that it can be registered with that framework.
+    ///
+    /// As with [`Deref`](ops::Deref), any query parameters are not included.
+    ///
+    /// # Errors
+    ///
+    /// [`WebRouteError::UnsupportedByDialect`] if a segment cannot be rendered
+    /// in the syntax of `dialect` (e.g. it has a dotted parameter name, which
+    /// can only be used to populate a route).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, parameterized_route::dialect::Dialect};
+    ///
+    /// let route = ParameterizedRoute::new("/files/{user_id}/{*path}");
+    ///
+    /// assert_eq!(route.to_template_with(Dialect::Axum).unwrap(), "/files/{user_id}/{*path}");
+    /// assert_eq!(route.to_template_with(Dialect::ActixWeb).unwrap(), "/files/{user_id}/{path:.*}");
+    /// assert_eq!(route.to_template_with(Dialect::Rocket).unwrap(), "/files/<user_id>/<path..>");
+    /// assert_eq!(route.to_template_with(Dialect::Express).unwrap(), "/files/:user_id/*path");
+    ///
+    /// assert!(ParameterizedRoute::new("/users/{user.id}").to_template_with(Dialect::Express).is_err());
+    /// ```
+    pub fn to_template_with(&self, dialect: Dialect) -> Result<String, WebRouteError> {
+        let segments = self.to_segments();
+        for segment in &segments {
+            dialect.check_segment(segment).map_err(|reason| {
+                WebRouteError::UnsupportedByDialect {
+                    dialect,
+                    segment: segment.to_template(),
+                    reason,
+                }
+            })?;
+        }
+
+        Ok(render_path(&segments, dialect))
+    }
+
+    /// Expands the route's optional parameters into each of the concrete
+    /// routes it represents, from the shortest to the longest. None of the
+    /// expanded routes have optional parameters, and each keeps the route's
+    /// query parameters.
+    ///
+    /// This allows the route to be registered with frameworks that lack
+    /// optional segments. A route without optional parameters expands into
+    /// itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/reports/{year?}/{month?}{?format}");
+    ///
+    /// assert_eq!(
+    ///     route.expand_optional(),
+    ///     vec![
+    ///         ParameterizedRoute::new("/reports{?format}"),
+    ///         ParameterizedRoute::new("/reports/{year}{?format}"),
+    ///         ParameterizedRoute::new("/reports/{year}/{month}{?format}"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn expand_optional(&self) -> Vec<Self> {
+        let segments = self.to_segments();
+        let query_params = self.query_params();
+        let required_len = segments
+            .iter()
+            .position(ParameterizedSegment::is_optional)
+            .unwrap_or(segments.len());
+
+        (required_len..=segments.len())
+            .map(|len| {
+                let expanded_segments = segments[..len]
+                    .iter()
+                    .map(ParameterizedSegment::to_required)
+                    .collect();
+
+                Self::from_template(evaluate_segments(expanded_segments, query_params.clone()))
+            })
+            .collect()
     }
 
     /// Attempts to populate the parameters of the route with their `values` and
     /// returns a [`WebRoute`].
     ///
-    /// `values` needs to implement `serde::Serialize` and be of an "Object"
-    /// style (with key-value pairs).
+    /// `values` can be a reference to anything that implements
+    /// `serde::Serialize` and is of an "Object" style (with key-value pairs),
+    /// or anything else that implements [`RouteParams`] (e.g. a map or an
+    /// array of `(key, value)` pairs).
+    ///
+    /// The values are percent-encoded, so a named parameter always populates
+    /// exactly one segment. A catch-all parameter's value is split on `/` and
+    /// each of its segments is encoded individually.
+    ///
+    /// Optional parameters (e.g. `{year?}`) are omitted from the route if they
+    /// have no value (or a value of `None`).
+    ///
+    /// Query parameters are populated from the same `values` and form-encoded
+    /// into the route's query. Unlike path parameters they are optional, and
+    /// are omitted if they have no value (or a value of `None`).
     ///
     /// This would be used when making a request to an endpoint represented by
     /// the route.
     ///
+    /// Values that do not populate any parameter are ignored, see
+    /// [`ParameterizedRoute::to_web_route_strict`] to reject them.
+    ///
     /// # Errors
     ///
-    /// - [`WebRouteError::UnpopulatedParam`] if no matching entry was found in
-    ///   `values` for a particular parameter.
+    /// - [`WebRouteError::MismatchedParams`] listing every parameter for which
+    ///   no matching entry was found in `values`.
+    /// - [`WebRouteError::UnpopulatedParam`] if an optional parameter has no
+    ///   value while a later optional parameter does.
+    /// - [`WebRouteError::EmptyParamValue`] if the value of a path parameter
+    ///   (other than a catch-all) is empty.
     /// - [`WebRouteError::InvalidValue`] if `values` does not contain key-value
     ///   pairs.
     ///
@@ -84,102 +450,1674 @@ impl ParameterizedRoute {
     ///     
     /// assert_eq!(&web_route.to_string(), "/some/route/value")
     /// ```
-    pub fn to_web_route<V: serde::Serialize>(&self, values: &V) -> Result<WebRoute, WebRouteError> {
-        let values = struct_to_map(values).ok_or(WebRouteError::InvalidValue)?;
+    pub fn to_web_route<P: RouteParams>(&self, values: P) -> Result<WebRoute, WebRouteError> {
+        self.populate(&values, false)
+    }
 
-        let populated_segments = self
-            .to_segments()
-            .iter()
-            .map(|segment| segment.to_populated(&values))
-            .collect::<Result<Vec<_>, _>>()?;
+    /// Populates the route in the same way as
+    /// [`ParameterizedRoute::to_web_route`], but also rejects `values` that do
+    /// not populate any of its path or query parameters (e.g. because of a
+    /// typo in a field name).
+    ///
+    /// The names of the values are listed by [`RouteParams::param_names`], so
+    /// values looked up by a closure are never rejected. A nested value is
+    /// used if any of its fields populates a dotted parameter (e.g.
+    /// `{user.id}`).
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::MismatchedParams`] listing every parameter for which
+    ///   no matching entry was found in `values`, and every entry of `values`
+    ///   that does not populate a parameter.
+    /// - Any of the errors of [`ParameterizedRoute::to_web_route`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, error::WebRouteError};
+    ///
+    /// let route = ParameterizedRoute::new("/users/{user_id}");
+    /// let res = route.to_web_route_strict([("user_id", 1), ("usr_id", 2)]);
+    ///
+    /// assert!(matches!(
+    ///     res,
+    ///     Err(WebRouteError::MismatchedParams { unused, .. }) if unused == vec!["usr_id"]
+    /// ));
+    /// ```
+    pub fn to_web_route_strict<P: RouteParams>(
+        &self,
+        values: P,
+    ) -> Result<WebRoute, WebRouteError> {
+        self.populate(&values, true)
+    }
 
-        let web_route = WebRoute::new(format!("/{}", populated_segments.join("/")));
+    /// Populates the route with `values` in a single pass, collecting every
+    /// parameter that has no value and, if `strict`, every value that does
+    /// not populate a path or query parameter.
+    fn populate(&self, values: &dyn RouteParams, strict: bool) -> Result<WebRoute, WebRouteError> {
+        let mut missing = Vec::new();
+        // Any other error is only reported if no parameters are missing.
+        let mut error = None;
+        let mut populated_segments = Vec::new();
+        let mut omitted_param = None;
+        for segment in self.to_segments() {
+            if segment.is_optional() {
+                let param = segment.param().unwrap_or_default();
 
-        Ok(web_route)
-    }
+                if values.param(param)?.is_none() {
+                    omitted_param.get_or_insert_with(|| param.to_owned());
+                    continue;
+                }
 
-    pub(crate) fn to_segments(&self) -> Vec<ParameterizedSegment> {
-        ToParameterizedSegments::to_segments(&self.0)
-    }
-}
+                // Populating an optional parameter after omitting an earlier
+                // one would shift its value into the wrong position.
+                if let Some(omitted_param) = omitted_param.take() {
+                    error.get_or_insert(WebRouteError::UnpopulatedParam(omitted_param));
+                }
+            }
 
-impl fmt::Display for ParameterizedRoute {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
+            match segment.to_populated(values) {
+                Ok(populated) => populated_segments.push(populated),
+                Err(WebRouteError::UnpopulatedParam(param)) => match &segment {
+                    ParameterizedSegment::Mixed(_) => {
+                        // Only the first parameter without a value is
+                        // reported, so the others are looked up.
+                        for param in segment.params() {
+                            if values.param(param)?.is_none() {
+                                missing.push(param.to_owned());
+                            }
+                        }
+                    }
+                    _ => missing.push(param),
+                },
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
 
-impl fmt::Debug for ParameterizedRoute {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("ParameterizedRoute")
-            .field(&self.to_string())
-            .finish()
-    }
-}
+        let unused = if strict {
+            self.unused_params(values)?
+        } else {
+            Vec::new()
+        };
+        if !missing.is_empty() || !unused.is_empty() {
+            return Err(WebRouteError::MismatchedParams {
+                template: self.to_string(),
+                missing,
+                unused,
+            });
+        }
+        if let Some(error) = error {
+            return Err(error);
+        }
 
-/// Allows one to deref for usage with external crates. Makes for neater code.
-impl ops::Deref for ParameterizedRoute {
-    type Target = str;
+        let query_pairs = self
+            .query_params()
+            .into_iter()
+            .map(|param| {
+                Ok(values
+                    .param(&param)?
+                    .map(|value| (param, value.into_owned())))
+            })
+            .filter_map(Result::transpose)
+            .collect::<Result<Vec<_>, WebRouteError>>()?;
+        let query = serde_urlencoded::to_string(query_pairs)
+            .map_err(|err| WebRouteError::InvalidQuery(err.to_string()))?;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        let web_route = WebRoute::new(format!("/{}?{query}", populated_segments.join("/")));
+
+        Ok(web_route)
     }
-}
 
-impl AsRef<str> for ParameterizedRoute {
-    fn as_ref(&self) -> &str {
-        &self.0
+    /// The names of the `values` that do not populate any path or query
+    /// parameter.
+    fn unused_params(&self, values: &dyn RouteParams) -> Result<Vec<String>, WebRouteError> {
+        let params = [self.params(), self.query_params()].concat();
+
+        Ok(values
+            .param_names()?
+            .into_iter()
+            .filter(|name| {
+                !params.iter().any(|param| {
+                    param
+                        .strip_prefix(name.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+                })
+            })
+            .collect())
     }
-}
 
-#[cfg(feature = "fake")]
-impl fake::Dummy<fake::Faker> for ParameterizedRoute {
-    fn dummy_with_rng<R: fake::Rng + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
-        use fake::Fake;
+    /// Populates the parameters of the route that have a value in `values`,
+    /// returning a [`ParameterizedRoute`] in which the remaining parameters
+    /// are still templates.
+    ///
+    /// This allows a route to be populated in stages (e.g. the outer layer of
+    /// nested routers knows the `{tenant_id}`, and an inner layer the
+    /// `{item_id}`). The values are validated and percent-encoded in the same
+    /// way as by [`ParameterizedRoute::to_web_route`].
+    ///
+    /// Query parameters are left as they are, to be populated by
+    /// [`ParameterizedRoute::to_web_route`] once the route is complete.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::UnpopulatedParam`] if an optional parameter has no
+    ///   value while a later optional parameter does.
+    /// - [`WebRouteError::UnsatisfiedConstraint`] if a value does not satisfy
+    ///   the constraint of its parameter.
+    /// - [`WebRouteError::InvalidValue`] if `values` does not contain key-value
+    ///   pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, WebRoute};
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct TenantParams {
+    ///     tenant_id: String,
+    /// }
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct ItemParams {
+    ///     item_id: u32,
+    /// }
+    ///
+    /// let route = ParameterizedRoute::new("/tenants/{tenant_id}/items/{item_id}");
+    ///
+    /// let tenant_route = route
+    ///     .populate_partial(&TenantParams {
+    ///         tenant_id: "acme".to_owned(),
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(tenant_route, ParameterizedRoute::new("/tenants/acme/items/{item_id}"));
+    ///
+    /// let web_route = tenant_route
+    ///     .to_web_route(&ItemParams { item_id: 42 })
+    ///     .unwrap();
+    /// assert_eq!(web_route, WebRoute::new("/tenants/acme/items/42"));
+    /// ```
+    pub fn populate_partial<P: RouteParams>(&self, values: P) -> Result<Self, WebRouteError> {
+        let mut populated_segments = Vec::new();
+        let mut omitted_param = None;
+        for segment in self.to_segments() {
+            if segment.is_optional() {
+                let param = segment.param().unwrap_or_default();
 
-        let segments: Vec<ParameterizedSegment> = config.fake_with_rng(rng);
-        Self::new(segments)
+                if values.param(param)?.is_none() {
+                    omitted_param.get_or_insert_with(|| param.to_owned());
+                } else if let Some(omitted_param) = omitted_param {
+                    // A static segment cannot follow an optional parameter.
+                    return Err(WebRouteError::UnpopulatedParam(omitted_param));
+                }
+            }
+
+            populated_segments.extend(segment.to_partially_populated(&values)?);
+        }
+
+        Ok(Self::from_template(evaluate_segments(
+            populated_segments,
+            self.query_params(),
+        )))
     }
-}
 
-/// Convert `segments` into their normalized [`String`] route representation.
-fn evaluate_segments(segments: Vec<ParameterizedSegment>) -> String {
-    let evaluated_segments = segments
-        .iter()
-        .map(ParameterizedSegment::to_template)
-        .collect::<Vec<_>>();
+    /// Attempts to match a concrete `route` against the [`ParameterizedRoute`],
+    /// returning the values captured by each of its parameters.
+    ///
+    /// This is the inverse of [`ParameterizedRoute::to_web_route`]. The
+    /// captured values are percent-decoded. A catch-all parameter captures the
+    /// remaining segments of `route`, each decoded, joined by `/`.
+    ///
+    /// Returns [`None`] if the static segments do not line up, or if `route`
+    /// has too few or too many segments. Optional parameters that `route` has
+    /// no segments for are not captured.
+    ///
+    /// Any of the route's query parameters that are present in the query of
+    /// `route` are captured too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, WebRoute};
+    ///
+    /// let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}/bar/{*rest}");
+    /// let web_route = WebRoute::new("/foo/123/bar/a/b");
+    ///
+    /// let params = parameterized_route.match_route(&web_route).unwrap();
+    ///
+    /// assert_eq!(params["foo_id"], "123");
+    /// assert_eq!(params["rest"], "a/b");
+    /// assert!(parameterized_route.match_route("/baz/123/bar/a").is_none());
+    /// ```
+    pub fn match_route<R: ToFixedSegments>(&self, route: R) -> Option<HashMap<String, String>> {
+        let mut web_segments = route.to_segments().into_iter();
+        let mut params = HashMap::new();
 
-    format!("/{}", evaluated_segments.join("/"))
-}
+        for segment in self.to_segments() {
+            match segment {
+                ParameterizedSegment::Static(value) => {
+                    if web_segments.next()?.to_evaluated() != value {
+                        return None;
+                    }
+                }
+                ParameterizedSegment::NamedParam {
+                    name,
+                    constraint,
+                    optional,
+                } => {
+                    let value = match web_segments.next() {
+                        Some(web_segment) => web_segment.to_decoded(),
+                        // Only optional parameters follow an optional
+                        // parameter, so none of them are captured.
+                        None if optional => break,
+                        None => return None,
+                    };
 
-#[cfg(test)]
-mod parameterized_route_tests {
-    use super::*;
+                    if constraint.is_some_and(|constraint| !constraint.is_satisfied_by(&value)) {
+                        return None;
+                    }
 
-    mod to_web_route {
-        use std::ops::Deref;
+                    params.insert(name, value);
+                }
+                ParameterizedSegment::Mixed(parts) => {
+                    let values = match_parts(&parts, web_segments.next()?.as_str())?;
+                    let names = parts.into_iter().filter_map(|part| match part {
+                        SegmentPart::Param { name, .. } => Some(name),
+                        SegmentPart::Static(_) => None,
+                    });
 
-        use super::*;
+                    params.extend(names.zip(values));
+                }
+                ParameterizedSegment::CatchallParam(param) => {
+                    let remaining = web_segments
+                        .by_ref()
+                        .map(|segment| segment.to_decoded())
+                        .collect::<Vec<_>>();
 
-        #[test]
-        fn should_normalize_double_forward_slashes() {
-            // Arrange
-            #[derive(serde::Serialize)]
-            struct RouteParams {
-                param: String,
+                    if remaining.is_empty() {
+                        return None;
+                    }
+
+                    params.insert(param, remaining.join("/"));
+                }
             }
+        }
 
-            let parameterized_route = ParameterizedRoute::new("/some/route/{param}");
+        // All the segments of `route` need to have been consumed for it to be a
+        // match.
+        if web_segments.next().is_some() {
+            return None;
+        }
 
-            // Act
-            let web_route = parameterized_route
-                .to_web_route(&RouteParams {
-                    param: "/value".to_owned(),
-                })
-                .unwrap();
+        params.extend(declared_query_pairs(
+            route.to_query().as_deref(),
+            &self.query_params(),
+        ));
+
+        Some(params)
+    }
+
+    /// Attempts to match a concrete `route` against the [`ParameterizedRoute`]
+    /// (see [`ParameterizedRoute::match_route`]), deserializing the captured
+    /// parameter values into `T`.
+    ///
+    /// Returns `Ok(None)` if `route` does not match. Values captured by dotted
+    /// parameters (e.g. `{user.id}`) are keyed by their full name, rather than
+    /// being deserialized into nested values.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::InvalidParams`] if the captured values could not be
+    ///   deserialized into `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// #[derive(Debug, PartialEq, serde::Deserialize)]
+    /// struct RouteParams {
+    ///     foo_id: u32,
+    ///     bar_id: String,
+    /// }
+    ///
+    /// let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}/bar/{bar_id}");
+    /// let params = parameterized_route
+    ///     .match_route_as::<RouteParams, _>("/foo/123/bar/abc")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     params,
+    ///     Some(RouteParams {
+    ///         foo_id: 123,
+    ///         bar_id: "abc".to_owned(),
+    ///     })
+    /// );
+    /// ```
+    pub fn match_route_as<T: DeserializeOwned, R: ToFixedSegments>(
+        &self,
+        route: R,
+    ) -> Result<Option<T>, WebRouteError> {
+        self.match_route(route)
+            .map(|params| {
+                map_to_struct(params).map_err(|err| WebRouteError::InvalidParams(err.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Returns an iterator over the segments of the route's path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/users/{user_id}/files/{*path}{?download}");
+    /// let segments = route.segments().map(|segment| segment.to_string()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(segments, vec!["users", "{user_id}", "files", "{*path}"]);
+    /// ```
+    pub fn segments(&self) -> impl Iterator<Item = ParameterizedSegment> {
+        self.to_segments().into_iter()
+    }
+
+    /// Returns the names of the route's named and catch-all parameters, in
+    /// the order they appear in the path. Query parameters are returned by
+    /// [`ParameterizedRoute::query_params`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/users/{user_id}/files/{*path}{?download}");
+    ///
+    /// assert_eq!(route.params(), vec!["user_id", "path"]);
+    /// ```
+    pub fn params(&self) -> Vec<String> {
+        self.to_segments()
+            .iter()
+            .flat_map(ParameterizedSegment::params)
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    /// Returns the number of segments in the route's path (e.g. `0` for `/`).
+    pub fn segment_count(&self) -> usize {
+        self.to_segments().len()
+    }
+
+    /// Returns the first segment of the route's path, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/users/{user_id}/files/{*path}{?download}");
+    ///
+    /// assert_eq!(route.first().unwrap().to_string(), "users");
+    /// assert!(ParameterizedRoute::new("/").first().is_none());
+    /// ```
+    pub fn first(&self) -> Option<ParameterizedSegment> {
+        self.to_segments().into_iter().next()
+    }
+
+    /// Returns the last segment of the route's path, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/users/{user_id}/files/{*path}{?download}");
+    ///
+    /// assert_eq!(route.last().unwrap().to_string(), "{*path}");
+    /// assert!(ParameterizedRoute::new("/").last().is_none());
+    /// ```
+    pub fn last(&self) -> Option<ParameterizedSegment> {
+        self.to_segments().pop()
+    }
+
+    /// Returns the route without its last segment, or [`None`] if the route's
+    /// path has no segments.
+    ///
+    /// The query parameters of the route are not carried over to the parent, as
+    /// they belong to the route itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/users/{user_id}/files/{*path}{?download}");
+    ///
+    /// assert_eq!(route.parent(), Some(ParameterizedRoute::new("/users/{user_id}/files")));
+    /// assert_eq!(ParameterizedRoute::new("/").parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<Self> {
+        let mut segments = self.to_segments();
+        segments.pop()?;
+
+        Some(Self::from_template(evaluate_segments(segments, Vec::new())))
+    }
+
+    /// Creates a [`ParameterizedRoute`] from a normalized `template`.
+    fn from_template(template: String) -> Self {
+        let path = render_path(
+            &ToParameterizedSegments::to_segments(&template.as_str()),
+            Dialect::Axum,
+        );
+
+        Self {
+            template: Cow::Owned(template),
+            path: Cow::Owned(path),
+        }
+    }
+
+    pub(crate) fn to_segments(&self) -> Vec<ParameterizedSegment> {
+        ToParameterizedSegments::to_segments(&self.template.as_ref())
+    }
+}
+
+/// Displays the full template, including any query parameters.
+impl fmt::Display for ParameterizedRoute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
+
+impl fmt::Debug for ParameterizedRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ParameterizedRoute")
+            .field(&self.to_string())
+            .finish()
+    }
+}
+
+/// Allows one to deref for usage with external crates. Makes for neater code.
+///
+/// Derefs to the path template, without any query parameters, as this is what
+/// webservers use to register routes.
+impl ops::Deref for ParameterizedRoute {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+impl AsRef<str> for ParameterizedRoute {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+#[cfg(feature = "fake")]
+impl fake::Dummy<fake::Faker> for ParameterizedRoute {
+    fn dummy_with_rng<R: fake::Rng + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        use fake::Fake;
+
+        let segments: Vec<ParameterizedSegment> = config.fake_with_rng(rng);
+        Self::new(segments)
+    }
+}
+
+/// Strictly parses the comma separated query parameter names that follow a
+/// `{?`.
+fn parse_query_params(query: &str) -> Result<Vec<String>, WebRouteError> {
+    let params = query.strip_suffix('}').ok_or_else(|| {
+        WebRouteError::invalid_query_component(
+            &format!("{{?{query}"),
+            SyntaxError::UnclosedQueryParams,
+        )
+    })?;
+
+    params
+        .split(',')
+        .map(str::trim)
+        .map(|param| {
+            validate_param_name(param)
+                .map(|_| param.to_owned())
+                .map_err(|reason| {
+                    WebRouteError::invalid_query_component(&format!("{{?{query}"), reason)
+                })
+        })
+        .collect()
+}
+
+/// Renders the path template of `segments` in the syntax of `dialect`.
+fn render_path(segments: &[ParameterizedSegment], dialect: Dialect) -> String {
+    let rendered_segments = segments
+        .iter()
+        .map(|segment| segment.to_template_with(dialect))
+        .collect::<Vec<_>>();
+
+    format!("/{}", rendered_segments.join("/"))
+}
+
+/// Convert `segments` and `query_params` into their normalized [`String`]
+/// route representation.
+///
+/// Duplicate query parameters are only included once.
+fn evaluate_segments(segments: Vec<ParameterizedSegment>, query_params: Vec<String>) -> String {
+    let evaluated_segments = segments
+        .iter()
+        .map(ParameterizedSegment::to_template)
+        .collect::<Vec<_>>();
+
+    let mut unique_query_params = Vec::<String>::new();
+    for param in query_params {
+        if !unique_query_params.contains(&param) {
+            unique_query_params.push(param);
+        }
+    }
+
+    if unique_query_params.is_empty() {
+        format!("/{}", evaluated_segments.join("/"))
+    } else {
+        format!(
+            "/{}{{?{}}}",
+            evaluated_segments.join("/"),
+            unique_query_params.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod parameterized_route_tests {
+    use super::*;
+
+    mod to_web_route {
+        use std::ops::Deref;
+
+        use super::*;
+
+        #[test]
+        fn should_reject_empty_param_value_rather_than_drop_segment() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{id}/posts");
+
+            // Act
+            let res = route.to_web_route([("id", "")]);
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::EmptyParamValue(param)) if param == "id"));
+        }
+
+        #[test]
+        fn should_normalize_double_forward_slashes() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                param: String,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/some/route/{*param}");
+
+            // Act
+            let web_route = parameterized_route
+                .to_web_route(&RouteParams {
+                    param: "/value//nested/".to_owned(),
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(web_route.deref(), "/some/route/value/nested")
+        }
+
+        #[test]
+        fn should_encode_named_parameter_as_single_segment() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                param: String,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/some/route/{param}");
+
+            // Act
+            let web_route = parameterized_route
+                .to_web_route(&RouteParams {
+                    param: "/value".to_owned(),
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(web_route.deref(), "/some/route/%2Fvalue")
+        }
+
+        #[test]
+        fn should_encode_each_element_of_sequence_as_catchall_segment() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                path: Vec<String>,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/files/{*path}");
+
+            // Act
+            let web_route = parameterized_route
+                .to_web_route(&RouteParams {
+                    path: vec!["a/b".to_owned(), "my file.txt".to_owned()],
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(web_route.deref(), "/files/a%2Fb/my%20file.txt")
+        }
+
+        #[test]
+        fn should_populate_dotted_params_from_nested_fields() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct User {
+                id: u32,
+            }
+
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                user: User,
+                post: std::collections::HashMap<&'static str, &'static str>,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/users/{user.id}/posts/{post.slug}");
+
+            // Act
+            let web_route = parameterized_route
+                .to_web_route(&RouteParams {
+                    user: User { id: 42 },
+                    post: [("slug", "hello world")].into(),
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(web_route.deref(), "/users/42/posts/hello%20world")
+        }
+
+        #[test]
+        fn should_not_populate_dotted_params_from_missing_nested_fields() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                user: Option<u32>,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/users/{user.id}");
+
+            // Act
+            let res = parameterized_route.to_web_route(&RouteParams { user: Some(42) });
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::MismatchedParams { missing, .. }) if missing == vec!["user.id"]
+            ));
+        }
+    }
+
+    mod query_params {
+        use std::ops::Deref;
+
+        use super::*;
+
+        #[test]
+        fn should_exclude_query_params_from_path_template() {
+            // Act
+            let route = ParameterizedRoute::new("/items/{id}{?page,per_page}");
+
+            // Assert
+            assert_eq!(&route.to_string(), "/items/{id}{?page,per_page}");
+            assert_eq!(route.deref(), "/items/{id}");
+        }
+
+        #[test]
+        fn should_combine_query_params_when_joining() {
+            // Arrange
+            let route = ParameterizedRoute::new("/items{?page}");
+
+            // Act
+            let joined_route = route.join("/{id}{?page,expand}");
+
+            // Assert
+            assert_eq!(&joined_route.to_string(), "/items/{id}{?page,expand}");
+        }
+
+        #[test]
+        fn should_populate_query_params_omitting_missing_values() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                id: u32,
+                page: Option<u32>,
+                per_page: Option<u32>,
+            }
+
+            let route = ParameterizedRoute::new("/items/{id}{?page,per_page}");
+
+            // Act
+            let web_route = route
+                .to_web_route(&RouteParams {
+                    id: 1,
+                    page: Some(2),
+                    per_page: None,
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(&web_route.to_string(), "/items/1?page=2");
+        }
+
+        #[test]
+        fn should_form_encode_query_params() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct RouteParams {
+                q: String,
+            }
+
+            let route = ParameterizedRoute::new("/search{?q}");
+
+            // Act
+            let web_route = route
+                .to_web_route(&RouteParams {
+                    q: "a b&c".to_owned(),
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(&web_route.to_string(), "/search?q=a+b%26c");
+        }
+
+        #[test]
+        fn should_capture_query_params_when_matching() {
+            // Arrange
+            let route = ParameterizedRoute::new("/items/{id}{?page}");
+
+            // Act
+            let params = route.match_route("/items/1?page=2&other=3").unwrap();
+
+            // Assert
+            assert_eq!(
+                params,
+                HashMap::from([
+                    ("id".to_owned(), "1".to_owned()),
+                    ("page".to_owned(), "2".to_owned()),
+                ])
+            );
+        }
+    }
+
+    mod match_route {
+        use super::*;
+
+        #[test]
+        fn should_capture_named_parameters() {
+            // Arrange
+            let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}/bar/{bar_id}");
+
+            // Act
+            let params = parameterized_route
+                .match_route(WebRoute::new("/foo/1/bar/2"))
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                params,
+                HashMap::from([
+                    ("foo_id".to_owned(), "1".to_owned()),
+                    ("bar_id".to_owned(), "2".to_owned()),
+                ])
+            );
+        }
+
+        #[test]
+        fn should_capture_remaining_segments_with_catchall_parameter() {
+            // Arrange
+            let parameterized_route = ParameterizedRoute::new("/foo/{*path}");
+
+            // Act
+            let params = parameterized_route
+                .match_route(WebRoute::new("/foo/a/b/c"))
+                .unwrap();
+
+            // Assert
+            assert_eq!(params["path"], "a/b/c");
+        }
+
+        #[test]
+        fn should_not_match_mismatched_static_segment() {
+            // Arrange
+            let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}");
+
+            // Act
+            let params = parameterized_route.match_route(WebRoute::new("/bar/1"));
+
+            // Assert
+            assert!(params.is_none());
+        }
+
+        #[test]
+        fn should_not_match_different_segment_count() {
+            // Arrange
+            let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}");
+
+            // Act
+            let too_short = parameterized_route.match_route(WebRoute::new("/foo"));
+            let too_long = parameterized_route.match_route(WebRoute::new("/foo/1/bar"));
+
+            // Assert
+            assert!(too_short.is_none());
+            assert!(too_long.is_none());
+        }
+
+        #[test]
+        fn should_not_match_empty_catchall_parameter() {
+            // Arrange
+            let parameterized_route = ParameterizedRoute::new("/foo/{*path}");
+
+            // Act
+            let params = parameterized_route.match_route(WebRoute::new("/foo"));
+
+            // Assert
+            assert!(params.is_none());
+        }
+
+        #[test]
+        fn should_round_trip_with_to_web_route() {
+            // Arrange
+            #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+            struct RouteParams {
+                foo_id: String,
+                bar_id: u64,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}/bar/{bar_id}");
+            let params = RouteParams {
+                foo_id: "foo".to_owned(),
+                bar_id: 42,
+            };
+
+            // Act
+            let web_route = parameterized_route.to_web_route(&params).unwrap();
+            let matched = parameterized_route
+                .match_route_as::<RouteParams, _>(&web_route)
+                .unwrap();
+
+            // Assert
+            assert_eq!(matched, Some(params));
+        }
+
+        #[test]
+        fn should_round_trip_values_that_need_encoding() {
+            // Arrange
+            #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+            struct RouteParams {
+                name: String,
+                path: String,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/foo/{name}/{*path}");
+            let params = RouteParams {
+                name: "a/b c 100%".to_owned(),
+                path: "hello world/..".to_owned(),
+            };
+
+            // Act
+            let web_route = parameterized_route.to_web_route(&params).unwrap();
+            let matched = parameterized_route
+                .match_route_as::<RouteParams, _>(&web_route)
+                .unwrap();
+
+            // Assert
+            assert_eq!(matched, Some(params));
+        }
+
+        #[test]
+        fn should_error_if_params_cannot_be_deserialized() {
+            // Arrange
+            #[derive(Debug, serde::Deserialize)]
+            #[allow(dead_code)]
+            struct RouteParams {
+                foo_id: u64,
+            }
+
+            let parameterized_route = ParameterizedRoute::new("/foo/{foo_id}");
+
+            // Act
+            let res = parameterized_route.match_route_as::<RouteParams, _>("/foo/abc");
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::InvalidParams(_))));
+        }
+    }
+
+    mod dialect {
+        use crate::error::DialectError;
+
+        use super::*;
+
+        #[test]
+        fn should_round_trip_through_each_dialect() {
+            // Arrange
+            let route = ParameterizedRoute::new("/files/{user_id}/{*path}{?download}");
+
+            for dialect in [
+                Dialect::Axum,
+                Dialect::ActixWeb,
+                Dialect::Rocket,
+                Dialect::Express,
+            ] {
+                // Act
+                let template = route.to_template_with(dialect).unwrap();
+                let parsed = ParameterizedRoute::from_template_with(&template, dialect);
+
+                // Assert
+                assert_eq!(
+                    parsed.to_template_with(Dialect::Axum).unwrap(),
+                    "/files/{user_id}/{*path}",
+                    "{dialect:?} should round trip via {template}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_keep_query_params_when_parsing() {
+            // Act
+            let route =
+                ParameterizedRoute::from_template_with("/items/:id{?page}", Dialect::Express);
+
+            // Assert
+            assert_eq!(route, ParameterizedRoute::new("/items/{id}{?page}"));
+        }
+
+        #[test]
+        fn should_default_to_axum() {
+            // Arrange
+            let route = ParameterizedRoute::new("/files/{user_id}/{*path}");
+
+            // Act
+            let template = route.to_template_with(Dialect::default());
+
+            // Assert
+            assert_eq!(template.unwrap(), &*route);
+        }
+
+        #[test]
+        fn should_reject_dotted_params_in_every_dialect() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{user.id}/posts");
+
+            for dialect in [
+                Dialect::Axum,
+                Dialect::ActixWeb,
+                Dialect::Rocket,
+                Dialect::Express,
+            ] {
+                // Act
+                let res = route.to_template_with(dialect);
+
+                // Assert
+                assert!(
+                    matches!(
+                        &res,
+                        Err(WebRouteError::UnsupportedByDialect {
+                            segment,
+                            reason: DialectError::DottedParam(param),
+                            ..
+                        }) if segment == "{user.id}" && param == "user.id"
+                    ),
+                    "{dialect:?} should reject {res:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_reject_mixed_segments_for_rocket() {
+            // Arrange
+            let route = ParameterizedRoute::new("/files/{name}.{ext}");
+
+            // Act
+            let rocket = route.to_template_with(Dialect::Rocket);
+            let actix = route.to_template_with(Dialect::ActixWeb);
+
+            // Assert
+            assert!(matches!(
+                rocket,
+                Err(WebRouteError::UnsupportedByDialect {
+                    dialect: Dialect::Rocket,
+                    segment,
+                    reason: DialectError::MixedSegment,
+                }) if segment == "{name}.{ext}"
+            ));
+            assert_eq!(actix.unwrap(), "/files/{name}.{ext}");
+        }
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_normalize_valid_template() {
+            // Act
+            let route = ParameterizedRoute::parse("foo//{foo_id}/{*rest}/{?page, page}").unwrap();
+
+            // Assert
+            assert_eq!(&route.to_string(), "/foo/{foo_id}/{*rest}{?page}");
+        }
+
+        #[test]
+        fn should_report_offending_segment() {
+            // Act
+            let res = ParameterizedRoute::parse("/foo/{bar/baz}");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::InvalidSegment {
+                    position: 1,
+                    segment,
+                    reason: SyntaxError::UnbalancedBraces,
+                }) if segment == "{bar"
+            ));
+        }
+
+        #[test]
+        fn should_reject_empty_parameter_name() {
+            // Act
+            let res = ParameterizedRoute::parse("/{}/x");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::InvalidSegment {
+                    position: 0,
+                    reason: SyntaxError::EmptyParamName,
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn should_reject_unclosed_query_params() {
+            // Act
+            let res = ParameterizedRoute::parse("/items{?page/foo");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::InvalidQueryComponent {
+                    reason: SyntaxError::UnclosedQueryParams,
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn should_leave_new_lenient() {
+            // Act
+            let route = ParameterizedRoute::new("/foo/{bar/baz}");
+
+            // Assert
+            assert_eq!(route.to_segments().len(), 3);
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn should_accept_valid_route() {
+            // Act
+            let res = ParameterizedRoute::new("/{id}/x/{*rest}{?page}").validate();
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn should_report_duplicate_params() {
+            // Act
+            let res = ParameterizedRoute::new("/{id}/x/{id}/{name}/{name}").validate();
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::DuplicateParams(params)) if params == vec!["id", "name"]
+            ));
+        }
+
+        #[test]
+        fn should_report_query_param_duplicating_path_param() {
+            // Act
+            let res = ParameterizedRoute::new("/{id}{?id}").validate();
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::DuplicateParams(params)) if params == vec!["id"]
+            ));
+        }
+
+        #[test]
+        fn should_report_misplaced_catchall() {
+            // Act
+            let res = ParameterizedRoute::new("/{*rest}/more").validate();
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::MisplacedCatchall(param)) if param == "rest"
+            ));
+        }
+
+        #[test]
+        fn should_report_multiple_catchalls() {
+            // Act
+            let res = ParameterizedRoute::new("/{*first}/{*second}").validate();
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::MultipleCatchalls(params)) if params == vec!["first", "second"]
+            ));
+        }
+
+        #[test]
+        fn should_validate_when_parsing() {
+            // Act
+            let res = ParameterizedRoute::parse("/{id}/x/{id}");
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::DuplicateParams(_))));
+        }
+
+        #[test]
+        fn should_validate_when_try_joining() {
+            // Arrange
+            let route = ParameterizedRoute::new("/files/{*path}");
+
+            // Act
+            let res = route.try_join("/more");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::MisplacedCatchall(param)) if param == "path"
+            ));
+        }
+    }
+
+    mod constraints {
+        use super::*;
+
+        #[test]
+        fn should_deref_to_path_without_constraints() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{id:int}/posts{?page}");
+
+            // Act
+            let path = &*route;
+
+            // Assert
+            assert_eq!(path, "/users/{id}/posts");
+            assert_eq!(&route.to_string(), "/users/{id:int}/posts{?page}");
+        }
+
+        #[test]
+        fn should_reject_value_not_satisfying_constraint() {
+            // Arrange
+            #[derive(serde::Serialize)]
+            struct Params {
+                id: String,
+            }
+            let route = ParameterizedRoute::new("/users/{id:int}");
+
+            // Act
+            let res = route.to_web_route(&Params {
+                id: "me".to_owned(),
+            });
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::UnsatisfiedConstraint { param, value, constraint })
+                    if param == "id" && value == "me" && constraint == "int"
+            ));
+        }
+
+        #[test]
+        fn should_only_match_values_satisfying_constraint() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{id:int}");
+
+            // Act
+            let matched = route.match_route("/users/123");
+            let unmatched = route.match_route("/users/me");
+
+            // Assert
+            assert!(matched.is_some());
+            assert!(unmatched.is_none());
+        }
+
+        #[test]
+        fn should_reject_unknown_constraint_when_parsing() {
+            // Act
+            let res = ParameterizedRoute::parse("/users/{id:integer}");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::InvalidSegment {
+                    reason: SyntaxError::InvalidConstraint(_),
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn should_drop_unknown_constraint_when_lenient() {
+            // Act
+            let route = ParameterizedRoute::new("/users/{id:Int}");
+
+            // Assert
+            assert_eq!(route, ParameterizedRoute::new("/users/{id}"));
+            assert!(route.match_route("/users/42").is_some());
+        }
+    }
+
+    mod optional_params {
+        use super::*;
+
+        #[derive(serde::Serialize)]
+        struct Params {
+            year: Option<u32>,
+            month: Option<u32>,
+        }
+
+        #[test]
+        fn should_omit_missing_optional_params() {
+            // Arrange
+            let route = ParameterizedRoute::new("/reports/{year?}/{month?}");
+
+            // Act
+            let none = route.to_web_route(&Params {
+                year: None,
+                month: None,
+            });
+            let year = route.to_web_route(&Params {
+                year: Some(2024),
+                month: None,
+            });
+
+            // Assert
+            assert_eq!(none.unwrap(), WebRoute::new("/reports"));
+            assert_eq!(year.unwrap(), WebRoute::new("/reports/2024"));
+        }
+
+        #[test]
+        fn should_reject_optional_param_following_omitted_one() {
+            // Arrange
+            let route = ParameterizedRoute::new("/reports/{year?}/{month?}");
+
+            // Act
+            let res = route.to_web_route(&Params {
+                year: None,
+                month: Some(5),
+            });
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::UnpopulatedParam(param)) if param == "year"));
+        }
+
+        #[test]
+        fn should_match_with_and_without_optional_params() {
+            // Arrange
+            let route = ParameterizedRoute::new("/reports/{year?:int}");
+
+            // Act
+            let without = route.match_route("/reports");
+            let with = route.match_route("/reports/2024");
+            let unsatisfied = route.match_route("/reports/latest");
+
+            // Assert
+            assert_eq!(without, Some(HashMap::new()));
+            assert_eq!(
+                with,
+                Some(HashMap::from([("year".to_owned(), "2024".to_owned())]))
+            );
+            assert_eq!(unsatisfied, None);
+        }
+
+        #[test]
+        fn should_report_misplaced_optional_param() {
+            // Act
+            let res = ParameterizedRoute::parse("/reports/{year?}/summary");
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::MisplacedOptional(param)) if param == "year"));
+        }
+
+        #[test]
+        fn should_expand_into_itself_without_optional_params() {
+            // Arrange
+            let route = ParameterizedRoute::new("/reports/{year}{?format}");
+
+            // Act
+            let expanded = route.expand_optional();
+
+            // Assert
+            assert_eq!(expanded, vec![route]);
+        }
+
+        #[test]
+        fn should_deref_to_longest_expansion() {
+            // Arrange
+            let route = ParameterizedRoute::new("/reports/{year?:int}");
+
+            // Act
+            let path = &*route;
+
+            // Assert
+            assert_eq!(path, "/reports/{year}");
+            assert_eq!(&route.to_string(), "/reports/{year?:int}");
+        }
+    }
+
+    mod mismatched_params {
+        use std::{borrow::Cow, cell::RefCell, rc::Rc};
+
+        use super::*;
+
+        /// Records every lookup, to check that values are only looked up once.
+        #[derive(Default)]
+        struct RecordingParams {
+            lookups: Rc<RefCell<Vec<String>>>,
+        }
+
+        impl RouteParams for RecordingParams {
+            fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+                self.lookups.borrow_mut().push(name.to_owned());
+                Ok((name != "missing").then_some(Cow::Borrowed("value")))
+            }
+
+            fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+                self.lookups.borrow_mut().push("*".to_owned());
+                Ok(vec!["id".to_owned(), "extra".to_owned()])
+            }
+        }
+
+        #[test]
+        fn should_look_up_each_value_once() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{id}/{missing}{?tab}");
+            let values = RecordingParams::default();
+            let lookups = Rc::clone(&values.lookups);
+
+            // Act
+            let res = route.to_web_route_strict(values);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::MismatchedParams { missing, unused, .. })
+                    if missing == vec!["missing"] && unused == vec!["extra"]
+            ));
+            assert_eq!(*lookups.borrow(), vec!["id", "missing", "*"]);
+        }
+
+        #[derive(serde::Serialize)]
+        struct User {
+            id: u32,
+            name: String,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Params {
+            user: User,
+            page: Option<u32>,
+            tab: String,
+        }
+
+        fn params() -> Params {
+            Params {
+                user: User {
+                    id: 1,
+                    name: "me".to_owned(),
+                },
+                page: None,
+                tab: "posts".to_owned(),
+            }
+        }
+
+        #[test]
+        fn should_list_every_missing_param() {
+            // Arrange
+            let route = ParameterizedRoute::new("/orgs/{org}/users/{user.id}/{tab}/{*path}");
+
+            // Act
+            let res = route.to_web_route(&params());
+
+            // Assert
+            let Err(WebRouteError::MismatchedParams {
+                template,
+                missing,
+                unused,
+            }) = res
+            else {
+                panic!("expected mismatched params, got {res:?}");
+            };
+            assert_eq!(template, "/orgs/{org}/users/{user.id}/{tab}/{*path}");
+            assert_eq!(missing, vec!["org", "path"]);
+            assert!(unused.is_empty());
+        }
+
+        #[test]
+        fn should_ignore_unused_values_unless_strict() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{user.id}");
+
+            // Act
+            let lenient = route.to_web_route(&params());
+            let strict = route.to_web_route_strict(&params());
+
+            // Assert
+            assert_eq!(lenient.unwrap(), WebRoute::new("/users/1"));
+            assert!(matches!(
+                strict,
+                Err(WebRouteError::MismatchedParams { missing, unused, .. })
+                    if missing.is_empty() && unused == vec!["page", "tab"]
+            ));
+        }
+
+        #[test]
+        fn should_treat_query_params_and_nested_values_as_used_when_strict() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{user.name}/{tab}{?page}");
+
+            // Act
+            let web_route = route.to_web_route_strict(&params());
+
+            // Assert
+            assert_eq!(web_route.unwrap(), WebRoute::new("/users/me/posts"));
+        }
+
+        #[test]
+        fn should_report_missing_and_unused_values_together() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{user_id}/{tab}");
+
+            // Act
+            let res = route.to_web_route_strict(HashMap::from([("usr_id", "1"), ("tab", "x")]));
+
+            // Assert
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                "unable to populate `/users/{user_id}/{tab}`: no values for parameters: user_id; unused values: usr_id"
+            );
+        }
+
+        #[test]
+        fn should_not_reject_values_that_cannot_be_listed() {
+            // Arrange
+            let route = ParameterizedRoute::new("/users/{user_id}");
+
+            // Act
+            let web_route =
+                route.to_web_route_strict(crate::route_params::from_fn(|_| Some("1".to_owned())));
+
+            // Assert
+            assert_eq!(web_route.unwrap(), WebRoute::new("/users/1"));
+        }
+    }
+
+    mod populate_partial {
+        use super::*;
+
+        #[derive(serde::Serialize)]
+        struct Params {
+            tenant_id: String,
+            name: Option<String>,
+            path: Option<String>,
+        }
+
+        #[test]
+        fn should_leave_unpopulated_params_as_templates() {
+            // Arrange
+            let route = ParameterizedRoute::new(
+                "/tenants/{tenant_id:slug}/files/{name}.{ext}/{*path}{?page}",
+            );
+
+            // Act
+            let populated = route
+                .populate_partial(&Params {
+                    tenant_id: "acme-corp".to_owned(),
+                    name: Some("annual report".to_owned()),
+                    path: Some("a/b".to_owned()),
+                })
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                populated,
+                ParameterizedRoute::new(
+                    "/tenants/acme-corp/files/annual%20report.{ext}/a/b{?page}"
+                )
+            );
+        }
+
+        #[test]
+        fn should_equal_to_web_route_once_complete() {
+            // Arrange
+            let route = ParameterizedRoute::new("/tenants/{tenant_id}/files/{name}.{ext}");
+            let params = Params {
+                tenant_id: "a/b".to_owned(),
+                name: Some("report".to_owned()),
+                path: None,
+            };
+
+            // Act
+            let populated = route
+                .populate_partial(&params)
+                .unwrap()
+                .to_web_route(HashMap::from([("ext", "pdf")]))
+                .unwrap();
+
+            // Assert
+            assert_eq!(&*populated, "/tenants/a%2Fb/files/report.pdf");
+        }
+
+        #[test]
+        fn should_reject_value_not_satisfying_constraint() {
+            // Arrange
+            let route = ParameterizedRoute::new("/tenants/{tenant_id:int}");
+
+            // Act
+            let res = route.populate_partial(&Params {
+                tenant_id: "acme".to_owned(),
+                name: None,
+                path: None,
+            });
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::UnsatisfiedConstraint { .. })
+            ));
+        }
+
+        #[test]
+        fn should_reject_optional_param_following_unpopulated_one() {
+            // Arrange
+            let route = ParameterizedRoute::new("/reports/{year?}/{name?}");
+
+            // Act
+            let res = route.populate_partial(&Params {
+                tenant_id: "acme".to_owned(),
+                name: Some("summary".to_owned()),
+                path: None,
+            });
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::UnpopulatedParam(param)) if param == "year"));
+        }
+    }
+
+    mod mixed_segments {
+        use super::*;
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Params {
+            version: u32,
+            name: String,
+            ext: String,
+        }
+
+        #[test]
+        fn should_round_trip_mixed_segments() {
+            // Arrange
+            let route =
+                ParameterizedRoute::parse("/api/v{version:int}/files/{name}.{ext}").unwrap();
+            let params = Params {
+                version: 2,
+                name: "annual report".to_owned(),
+                ext: "pdf".to_owned(),
+            };
+
+            // Act
+            let web_route = route.to_web_route(&params).unwrap();
+            let matched = route.match_route_as::<Params, _>(&web_route).unwrap();
+
+            // Assert
+            assert_eq!(&*web_route, "/api/v2/files/annual%20report.pdf");
+            assert_eq!(matched, Some(params));
+            assert_eq!(route.params(), vec!["version", "name", "ext"]);
+            assert_eq!(&*route, "/api/v{version}/files/{name}.{ext}");
+        }
+
+        #[test]
+        fn should_not_match_mixed_segment_with_unsatisfied_constraint() {
+            // Arrange
+            let route = ParameterizedRoute::new("/api/v{version:int}");
+
+            // Act
+            let matched = route.match_route("/api/vnext");
+
+            // Assert
+            assert!(matched.is_none());
+        }
+
+        #[test]
+        fn should_report_duplicate_params_within_mixed_segment() {
+            // Act
+            let res = ParameterizedRoute::parse("/{name}.{name}");
 
             // Assert
-            assert_eq!(web_route.deref(), "/some/route/value")
+            assert!(
+                matches!(res, Err(WebRouteError::DuplicateParams(params)) if params == ["name"])
+            );
         }
     }
 }
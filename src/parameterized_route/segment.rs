@@ -1,6 +1,15 @@
-use std::collections::HashMap;
-
-use crate::{error::WebRouteError, web_route::segment::WebSegment};
+use std::fmt;
+
+use crate::{
+    error::{SyntaxError, WebRouteError},
+    parameterized_route::{
+        constraint::Constraint,
+        dialect::{Dialect, TemplatePart, split_delimited},
+    },
+    route_params::RouteParams,
+    utils::{decode_segment, encode_segment, validate_chars, validate_param_name},
+    web_route::segment::WebSegment,
+};
 
 /// Represents an individual segment of a route (i.e. the bit between the `/`).
 ///
@@ -8,71 +17,519 @@ use crate::{error::WebRouteError, web_route::segment::WebSegment};
 /// and the populated version.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "fake", derive(fake::Dummy))]
+#[non_exhaustive]
 pub enum ParameterizedSegment {
-    NamedParam(String),
+    /// A parameter matching exactly one segment (e.g. `{id}`, `{id:int}` or
+    /// `{id?}`).
+    NamedParam {
+        /// The name of the parameter.
+        name: String,
+        /// The constraint its values need to satisfy, if any.
+        constraint: Option<Constraint>,
+        /// Whether the segment can be omitted. Only trailing parameters can be
+        /// optional.
+        optional: bool,
+    },
+    /// A parameter matching all remaining segments (e.g. `{*rest}`).
     CatchallParam(String),
+    /// A segment that is matched literally.
     Static(String),
+    /// A segment made up of static text and one or more parameters (e.g.
+    /// `{name}.{ext}` or `v{version}`). Each parameter matches at least one
+    /// character, and takes as many as it can.
+    Mixed(Vec<SegmentPart>),
+}
+
+/// A part of a [`ParameterizedSegment::Mixed`] segment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "fake", derive(fake::Dummy))]
+#[non_exhaustive]
+pub enum SegmentPart {
+    /// A parameter matching part of a segment (e.g. `{ext}` or `{ext:slug}`).
+    Param {
+        /// The name of the parameter.
+        name: String,
+        /// The constraint its values need to satisfy, if any.
+        constraint: Option<Constraint>,
+    },
+    /// Text that is matched literally.
+    Static(String),
+}
+
+impl SegmentPart {
+    /// Converts the part into its "templated" representation, including any
+    /// constraint.
+    fn to_template(&self) -> String {
+        match self {
+            SegmentPart::Param {
+                name,
+                constraint: Some(constraint),
+            } => format!("{{{name}:{constraint}}}"),
+            _ => self.to_template_with(Dialect::Axum),
+        }
+    }
+
+    /// Converts the part into its "templated" representation in the syntax of
+    /// a particular `dialect`.
+    fn to_template_with(&self, dialect: Dialect) -> String {
+        match self {
+            SegmentPart::Param { name, constraint } => {
+                dialect.named_param(name, constraint.as_ref(), false)
+            }
+            SegmentPart::Static(value) => value.to_owned(),
+        }
+    }
 }
 
 impl ParameterizedSegment {
+    /// Returns the name of the parameter, if the segment is a single
+    /// parameter. See [`ParameterizedSegment::params`] for mixed segments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/files/{*path}");
+    ///
+    /// assert_eq!(route.first().unwrap().param(), None);
+    /// assert_eq!(route.last().unwrap().param(), Some("path"));
+    /// ```
+    pub fn param(&self) -> Option<&str> {
+        match self {
+            ParameterizedSegment::NamedParam { name: param, .. }
+            | ParameterizedSegment::CatchallParam(param) => Some(param),
+            ParameterizedSegment::Static(_) | ParameterizedSegment::Mixed(_) => None,
+        }
+    }
+
+    /// Returns the names of all of the parameters in the segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/files/{name}.{ext}");
+    ///
+    /// assert_eq!(route.last().unwrap().params(), vec!["name", "ext"]);
+    /// assert_eq!(route.last().unwrap().param(), None);
+    /// ```
+    pub fn params(&self) -> Vec<&str> {
+        match self {
+            ParameterizedSegment::Mixed(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    SegmentPart::Param { name, .. } => Some(name.as_str()),
+                    SegmentPart::Static(_) => None,
+                })
+                .collect(),
+            _ => self.param().into_iter().collect(),
+        }
+    }
+
+    /// Returns the constraint of the parameter, if the segment is a named
+    /// parameter with one.
+    pub fn constraint(&self) -> Option<&Constraint> {
+        match self {
+            ParameterizedSegment::NamedParam { constraint, .. } => constraint.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the segment is an optional named parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::ParameterizedRoute;
+    ///
+    /// let route = ParameterizedRoute::new("/reports/{year?}");
+    ///
+    /// assert!(!route.first().unwrap().is_optional());
+    /// assert!(route.last().unwrap().is_optional());
+    /// ```
+    pub fn is_optional(&self) -> bool {
+        matches!(
+            self,
+            ParameterizedSegment::NamedParam { optional: true, .. }
+        )
+    }
+
     /// Converts the [`Segment`] into its "templated" representation so that it
     /// can be used in route definitions.
+    ///
+    /// Unlike [`Dialect::Axum`], this includes any constraints and optional
+    /// markers.
     pub(crate) fn to_template(&self) -> String {
         match self {
-            ParameterizedSegment::NamedParam(named_param) => format!("{{{named_param}}}"),
-            ParameterizedSegment::CatchallParam(catchall_param) => format!("{{*{catchall_param}}}"),
+            ParameterizedSegment::NamedParam {
+                name,
+                constraint,
+                optional,
+            } => {
+                let optional = if *optional { "?" } else { "" };
+                match constraint {
+                    Some(constraint) => format!("{{{name}{optional}:{constraint}}}"),
+                    None => format!("{{{name}{optional}}}"),
+                }
+            }
+            ParameterizedSegment::Mixed(parts) => {
+                parts.iter().map(SegmentPart::to_template).collect()
+            }
+            _ => self.to_template_with(Dialect::Axum),
+        }
+    }
+
+    /// Returns a copy of the segment that is no longer optional.
+    pub(crate) fn to_required(&self) -> Self {
+        match self {
+            ParameterizedSegment::NamedParam {
+                name, constraint, ..
+            } => ParameterizedSegment::NamedParam {
+                name: name.to_owned(),
+                constraint: constraint.clone(),
+                optional: false,
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Converts the [`Segment`] into its "templated" representation in the
+    /// syntax of a particular `dialect`.
+    pub(crate) fn to_template_with(&self, dialect: Dialect) -> String {
+        match self {
+            ParameterizedSegment::NamedParam {
+                name,
+                constraint,
+                optional,
+            } => dialect.named_param(name, constraint.as_ref(), *optional),
+            ParameterizedSegment::CatchallParam(catchall_param) => {
+                dialect.catchall_param(catchall_param)
+            }
             ParameterizedSegment::Static(value) => value.to_owned(),
+            ParameterizedSegment::Mixed(parts) => parts
+                .iter()
+                .map(|part| part.to_template_with(dialect))
+                .collect(),
         }
     }
 
-    /// Attempts to populate a parameter based [`Segment`] with a value in the
-    /// `param_value_map`. If the [`Segment`] is static, it internal value will
-    /// be returned.
+    /// Parses a `segment` from its "templated" representation in the syntax of
+    /// a particular `dialect`. Anything that is not a parameter is treated as a
+    /// static segment.
     ///
     /// # Errors
     ///
-    /// A [`WebRouteError::UnpopulatedParam`] if no matching entry was found in
-    /// the `param_value_map`.
-    pub(crate) fn to_populated(
-        &self,
-        param_value_map: &HashMap<String, String>,
-    ) -> Result<String, WebRouteError> {
+    /// A [`SyntaxError::EmptySegment`] if `segment` is empty.
+    pub(crate) fn from_template_with(segment: &str, dialect: Dialect) -> Result<Self, SyntaxError> {
+        let segment = segment.trim();
+
+        if segment.is_empty() {
+            return Err(SyntaxError::EmptySegment);
+        }
+
+        Ok(dialect
+            .parse_param(segment)
+            .unwrap_or_else(|| ParameterizedSegment::Static(segment.to_string())))
+    }
+
+    /// Strictly parses a `segment` from its "templated" representation.
+    ///
+    /// Unlike [`TryFrom`], malformed segments are rejected rather than being
+    /// treated as static segments.
+    ///
+    /// # Errors
+    ///
+    /// The [`SyntaxError`] describing why `segment` is malformed.
+    pub(crate) fn parse(segment: &str) -> Result<Self, SyntaxError> {
+        if segment.is_empty() {
+            return Err(SyntaxError::EmptySegment);
+        }
+
+        let parts = split_delimited(segment, '{', '}').ok_or(SyntaxError::UnbalancedBraces)?;
+
+        match parts.as_slice() {
+            [TemplatePart::Param(param)] => match param.strip_prefix('*') {
+                Some(param) => {
+                    validate_param_name(param)?;
+                    Ok(ParameterizedSegment::CatchallParam(param.to_owned()))
+                }
+                None => {
+                    let (name, constraint) = match param.split_once(':') {
+                        Some((name, constraint)) => (name, Some(Constraint::parse(constraint)?)),
+                        None => (*param, None),
+                    };
+                    let (name, optional) = match name.strip_suffix('?') {
+                        Some(name) => (name, true),
+                        None => (name, false),
+                    };
+                    validate_param_name(name)?;
+
+                    Ok(ParameterizedSegment::NamedParam {
+                        name: name.to_owned(),
+                        constraint,
+                        optional,
+                    })
+                }
+            },
+            [TemplatePart::Static(value)] => {
+                validate_chars(value, &['?', '#'])?;
+                Ok(ParameterizedSegment::Static((*value).to_owned()))
+            }
+            parts => {
+                if parts
+                    .windows(2)
+                    .any(|pair| matches!(pair, [TemplatePart::Param(_), TemplatePart::Param(_)]))
+                {
+                    return Err(SyntaxError::AdjacentParams);
+                }
+
+                let parts = parts
+                    .iter()
+                    .map(|part| match part {
+                        TemplatePart::Param(param) if param.starts_with('*') => {
+                            Err(SyntaxError::PartialCatchall)
+                        }
+                        TemplatePart::Param(param) => {
+                            let (name, constraint) = match param.split_once(':') {
+                                Some((name, constraint)) => {
+                                    (name, Some(Constraint::parse(constraint)?))
+                                }
+                                None => (*param, None),
+                            };
+                            validate_param_name(name)?;
+
+                            Ok(SegmentPart::Param {
+                                name: name.to_owned(),
+                                constraint,
+                            })
+                        }
+                        TemplatePart::Static(value) => {
+                            validate_chars(value, &['?', '#'])?;
+                            Ok(SegmentPart::Static((*value).to_owned()))
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ParameterizedSegment::Mixed(parts))
+            }
+        }
+    }
+
+    /// Attempts to populate a parameter based [`Segment`] with a value in
+    /// `values`. If the [`Segment`] is static, it internal value will be
+    /// returned.
+    ///
+    /// Values are percent-encoded. A named parameter's value is encoded as a
+    /// single segment, whereas each of the segments of a catch-all parameter's
+    /// value (see [`RouteParams::catchall_param`]) is encoded individually.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::UnpopulatedParam`] if no matching entry was found in
+    ///   `values`.
+    /// - [`WebRouteError::EmptyParamValue`] if the value of a named parameter
+    ///   (including one within a mixed segment) is empty.
+    /// - [`WebRouteError::UnsatisfiedConstraint`] if the value of a named
+    ///   parameter does not satisfy its constraint.
+    pub(crate) fn to_populated(&self, values: &dyn RouteParams) -> Result<String, WebRouteError> {
         let populated = match self {
-            ParameterizedSegment::NamedParam(param) => param_value_map
-                .get(param)
-                .ok_or(WebRouteError::UnpopulatedParam(param.to_owned()))?
-                .to_owned(),
-            ParameterizedSegment::CatchallParam(param) => param_value_map
-                .get(param)
+            ParameterizedSegment::NamedParam {
+                name, constraint, ..
+            } => populate_param(name, constraint.as_ref(), values)?,
+            ParameterizedSegment::CatchallParam(param) => values
+                .catchall_param(param)?
                 .ok_or(WebRouteError::UnpopulatedParam(param.to_owned()))?
-                .to_owned(),
+                .iter()
+                .map(|segment| encode_segment(segment))
+                .collect::<Vec<_>>()
+                .join("/"),
             ParameterizedSegment::Static(value) => value.to_owned(),
+            ParameterizedSegment::Mixed(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    SegmentPart::Param { name, constraint } => {
+                        populate_param(name, constraint.as_ref(), values)
+                    }
+                    SegmentPart::Static(value) => Ok(value.to_owned()),
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        Ok(populated)
+    }
+
+    /// Populates the parameters of the [`Segment`] that have a value in
+    /// `values`, leaving the others as templates.
+    ///
+    /// A fully populated segment becomes one or more static segments (one for
+    /// each segment of a catch-all parameter's value), encoded in the same way
+    /// as by [`ParameterizedSegment::to_populated`].
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::EmptyParamValue`] if the value of a named parameter
+    ///   is empty.
+    /// - [`WebRouteError::UnsatisfiedConstraint`] if the value of a named
+    ///   parameter does not satisfy its constraint.
+    pub(crate) fn to_partially_populated(
+        &self,
+        values: &dyn RouteParams,
+    ) -> Result<Vec<Self>, WebRouteError> {
+        let is_populated = |param: &str| values.param(param).map(|value| value.is_some());
+        let is_fully_populated = match self {
+            ParameterizedSegment::NamedParam { name, .. } => is_populated(name)?,
+            ParameterizedSegment::CatchallParam(name) => values.catchall_param(name)?.is_some(),
+            _ => false,
+        };
+
+        let populated = match self {
+            ParameterizedSegment::NamedParam { .. } | ParameterizedSegment::CatchallParam(_)
+                if is_fully_populated =>
+            {
+                self.to_populated(values)?
+                    .split('/')
+                    .filter(|segment| !segment.is_empty())
+                    .map(|segment| ParameterizedSegment::Static(segment.to_owned()))
+                    .collect()
+            }
+            ParameterizedSegment::Mixed(parts) => {
+                let mut populated_parts = Vec::<SegmentPart>::new();
+                for part in parts {
+                    let populated_part = match part {
+                        SegmentPart::Param { name, constraint } if is_populated(name)? => {
+                            SegmentPart::Static(populate_param(name, constraint.as_ref(), values)?)
+                        }
+                        part => part.clone(),
+                    };
+
+                    // Merge adjacent static text, so that a fully populated
+                    // segment ends up as a single static part.
+                    match (populated_parts.last_mut(), populated_part) {
+                        (Some(SegmentPart::Static(text)), SegmentPart::Static(value)) => {
+                            text.push_str(&value)
+                        }
+                        (_, populated_part) => populated_parts.push(populated_part),
+                    }
+                }
+
+                match populated_parts.as_slice() {
+                    [SegmentPart::Static(value)] => {
+                        vec![ParameterizedSegment::Static(value.to_owned())]
+                    }
+                    _ => vec![ParameterizedSegment::Mixed(populated_parts)],
+                }
+            }
+            _ => vec![self.clone()],
         };
 
         Ok(populated)
     }
 }
 
-/// It is often a path of insecure traversals if there are two consecutive slashes in a path. Making an empty [`ParameterizedSegment`] impossible to create removes the chance of consecutive slashes.
-impl TryFrom<&str> for ParameterizedSegment {
-    type Error = ();
+/// Populates a named parameter with its percent-encoded value from `values`.
+///
+/// An empty value is rejected, as it would otherwise collapse the segment (or
+/// the parameter's part of a mixed segment) and shift the rest of the route.
+fn populate_param(
+    name: &str,
+    constraint: Option<&Constraint>,
+    values: &dyn RouteParams,
+) -> Result<String, WebRouteError> {
+    let value = values
+        .param(name)?
+        .ok_or(WebRouteError::UnpopulatedParam(name.to_owned()))?;
+
+    if value.is_empty() {
+        return Err(WebRouteError::EmptyParamValue(name.to_owned()));
+    }
 
-    fn try_from(segment: &str) -> Result<Self, Self::Error> {
-        let segment = segment.trim();
+    if let Some(constraint) = constraint
+        && !constraint.is_satisfied_by(&value)
+    {
+        return Err(WebRouteError::UnsatisfiedConstraint {
+            param: name.to_owned(),
+            value: value.into_owned(),
+            constraint: constraint.to_string(),
+        });
+    }
 
-        if segment.is_empty() {
-            return Err(());
-        }
+    Ok(encode_segment(&value))
+}
 
-        Ok(if segment.starts_with("{*") && segment.ends_with('}') {
-            let param = segment.trim_start_matches("{*").trim_end_matches('}');
-            ParameterizedSegment::CatchallParam(param.to_string())
-        } else if segment.starts_with('{') && segment.ends_with('}') {
-            let param = segment.trim_start_matches('{').trim_end_matches('}');
-            ParameterizedSegment::NamedParam(param.to_string())
-        } else {
-            ParameterizedSegment::Static(segment.to_string())
+/// Returns `parts` with the names of their parameters removed, so that mixed
+/// segments matching the same values compare as equal.
+pub(crate) fn unnamed_parts(parts: &[SegmentPart]) -> Vec<SegmentPart> {
+    parts
+        .iter()
+        .map(|part| match part {
+            SegmentPart::Param { constraint, .. } => SegmentPart::Param {
+                name: String::new(),
+                constraint: constraint.clone(),
+            },
+            SegmentPart::Static(value) => SegmentPart::Static(value.to_owned()),
         })
+        .collect()
+}
+
+/// Matches the (percent-encoded) `segment` against the `parts` of a mixed
+/// segment, returning the decoded value captured by each of its parameters.
+///
+/// Each parameter captures as much as it can while still allowing the rest of
+/// the parts to match (e.g. `{name}.{ext}` captures `archive.tar` and `gz` from
+/// `archive.tar.gz`).
+pub(crate) fn match_parts(parts: &[SegmentPart], segment: &str) -> Option<Vec<String>> {
+    let mut captures = Vec::new();
+
+    match_parts_into(parts, segment, &mut captures).then_some(captures)
+}
+
+fn match_parts_into(parts: &[SegmentPart], segment: &str, captures: &mut Vec<String>) -> bool {
+    match parts.split_first() {
+        None => segment.is_empty(),
+        Some((SegmentPart::Static(value), remaining_parts)) => segment
+            .strip_prefix(value.as_str())
+            .is_some_and(|remaining| match_parts_into(remaining_parts, remaining, captures)),
+        Some((SegmentPart::Param { constraint, .. }, remaining_parts)) => {
+            for end in (1..=segment.len()).rev() {
+                if !segment.is_char_boundary(end) {
+                    continue;
+                }
+
+                let value = decode_segment(&segment[..end]);
+                if constraint
+                    .as_ref()
+                    .is_some_and(|constraint| !constraint.is_satisfied_by(&value))
+                {
+                    continue;
+                }
+
+                captures.push(value);
+                if match_parts_into(remaining_parts, &segment[end..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+
+            false
+        }
+    }
+}
+
+/// Displays the "templated" representation of the segment.
+impl fmt::Display for ParameterizedSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_template())
+    }
+}
+
+/// It is often a path of insecure traversals if there are two consecutive slashes in a path. Making an empty [`ParameterizedSegment`] impossible to create removes the chance of consecutive slashes.
+impl TryFrom<&str> for ParameterizedSegment {
+    type Error = SyntaxError;
+
+    fn try_from(segment: &str) -> Result<Self, Self::Error> {
+        Self::from_template_with(segment, Dialect::Axum)
     }
 }
 
@@ -99,7 +556,11 @@ mod segment_tests {
         #[test]
         fn should_correctly_template_named_parameter() {
             // Arrange
-            let segment = ParameterizedSegment::NamedParam("named_param".to_owned());
+            let segment = ParameterizedSegment::NamedParam {
+                name: "named_param".to_owned(),
+                constraint: None,
+                optional: false,
+            };
 
             // Act
             let template = segment.to_template();
@@ -108,6 +569,22 @@ mod segment_tests {
             assert_eq!(template, "{named_param}");
         }
 
+        #[test]
+        fn should_correctly_template_optional_parameter() {
+            // Arrange
+            let segment = ParameterizedSegment::NamedParam {
+                name: "year".to_owned(),
+                constraint: Some(Constraint::Int),
+                optional: true,
+            };
+
+            // Act
+            let template = segment.to_template();
+
+            // Assert
+            assert_eq!(template, "{year?:int}");
+        }
+
         #[test]
         fn should_correctly_template_catchall_parameter() {
             // Arrange
@@ -133,9 +610,315 @@ mod segment_tests {
         }
     }
 
+    mod to_populated {
+        use std::collections::HashMap;
+
+        use super::*;
+
+        #[test]
+        fn should_encode_named_parameter_as_single_segment() {
+            // Arrange
+            let segment = ParameterizedSegment::NamedParam {
+                name: "param".to_owned(),
+                constraint: None,
+                optional: false,
+            };
+            let values = HashMap::from([("param".to_owned(), "a/b c".to_owned())]);
+
+            // Act
+            let populated = segment.to_populated(&values).unwrap();
+
+            // Assert
+            assert_eq!(populated, "a%2Fb%20c");
+        }
+
+        #[test]
+        fn should_reject_empty_named_and_mixed_values() {
+            // Arrange
+            let named = ParameterizedSegment::try_from("{id}").unwrap();
+            let mixed = ParameterizedSegment::try_from("{name}.{ext}").unwrap();
+            let values = HashMap::from([
+                ("id".to_owned(), String::new()),
+                ("name".to_owned(), "report".to_owned()),
+                ("ext".to_owned(), String::new()),
+            ]);
+
+            // Act
+            let named_res = named.to_populated(&values);
+            let mixed_res = mixed.to_populated(&values);
+
+            // Assert
+            assert!(
+                matches!(named_res, Err(WebRouteError::EmptyParamValue(param)) if param == "id")
+            );
+            assert!(
+                matches!(mixed_res, Err(WebRouteError::EmptyParamValue(param)) if param == "ext")
+            );
+        }
+
+        #[test]
+        fn should_encode_catchall_parameter_per_segment() {
+            // Arrange
+            let segment = ParameterizedSegment::CatchallParam("param".to_owned());
+            let values = HashMap::from([("param".to_owned(), "/a b//100%/".to_owned())]);
+
+            // Act
+            let populated = segment.to_populated(&values).unwrap();
+
+            // Assert
+            assert_eq!(populated, "a%20b/100%25");
+        }
+
+        #[test]
+        fn should_encode_params_of_mixed_segment() {
+            // Arrange
+            let segment = ParameterizedSegment::try_from("{name}.{ext}").unwrap();
+            let values = HashMap::from([
+                ("name".to_owned(), "my report".to_owned()),
+                ("ext".to_owned(), "pdf".to_owned()),
+            ]);
+
+            // Act
+            let populated = segment.to_populated(&values).unwrap();
+
+            // Assert
+            assert_eq!(populated, "my%20report.pdf");
+        }
+
+        #[test]
+        fn should_not_encode_static_segment() {
+            // Arrange
+            let segment = ParameterizedSegment::Static("static".to_owned());
+
+            // Act
+            let populated = segment
+                .to_populated(&HashMap::<String, String>::new())
+                .unwrap();
+
+            // Assert
+            assert_eq!(populated, "static");
+        }
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_each_segment_type() {
+            // Act
+            let named = ParameterizedSegment::parse("{named_param}");
+            let catchall = ParameterizedSegment::parse("{*catchall_param}");
+            let fixed = ParameterizedSegment::parse("static");
+
+            // Assert
+            assert_eq!(
+                named,
+                Ok(ParameterizedSegment::NamedParam {
+                    name: "named_param".to_owned(),
+                    constraint: None,
+                    optional: false,
+                })
+            );
+            assert_eq!(
+                catchall,
+                Ok(ParameterizedSegment::CatchallParam(
+                    "catchall_param".to_owned()
+                ))
+            );
+            assert_eq!(fixed, Ok(ParameterizedSegment::Static("static".to_owned())));
+        }
+
+        #[test]
+        fn should_parse_optional_parameters() {
+            // Act
+            let optional = ParameterizedSegment::parse("{year?}");
+            let constrained = ParameterizedSegment::parse("{year?:int}");
+
+            // Assert
+            assert_eq!(
+                optional,
+                Ok(ParameterizedSegment::NamedParam {
+                    name: "year".to_owned(),
+                    constraint: None,
+                    optional: true,
+                })
+            );
+            assert_eq!(
+                constrained,
+                Ok(ParameterizedSegment::NamedParam {
+                    name: "year".to_owned(),
+                    constraint: Some(Constraint::Int),
+                    optional: true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_parse_mixed_segment() {
+            // Act
+            let segment = ParameterizedSegment::parse("v{version:int}.{ext}");
+
+            // Assert
+            assert_eq!(
+                segment,
+                Ok(ParameterizedSegment::Mixed(vec![
+                    SegmentPart::Static("v".to_owned()),
+                    SegmentPart::Param {
+                        name: "version".to_owned(),
+                        constraint: Some(Constraint::Int),
+                    },
+                    SegmentPart::Static(".".to_owned()),
+                    SegmentPart::Param {
+                        name: "ext".to_owned(),
+                        constraint: None,
+                    },
+                ]))
+            );
+        }
+
+        #[test]
+        fn should_reject_ambiguous_mixed_segments() {
+            // Act
+            let adjacent = ParameterizedSegment::parse("{name}{ext}");
+            let catchall = ParameterizedSegment::parse("{*path}.json");
+
+            // Assert
+            assert_eq!(adjacent, Err(SyntaxError::AdjacentParams));
+            assert_eq!(catchall, Err(SyntaxError::PartialCatchall));
+        }
+
+        #[test]
+        fn should_reject_unbalanced_braces() {
+            // Act
+            let res = ParameterizedSegment::parse("{bar");
+
+            // Assert
+            assert_eq!(res, Err(SyntaxError::UnbalancedBraces));
+        }
+
+        #[test]
+        fn should_reject_empty_parameter_names() {
+            // Act
+            let named = ParameterizedSegment::parse("{}");
+            let catchall = ParameterizedSegment::parse("{*}");
+
+            // Assert
+            assert_eq!(named, Err(SyntaxError::EmptyParamName));
+            assert_eq!(catchall, Err(SyntaxError::EmptyParamName));
+        }
+
+        #[test]
+        fn should_reject_invalid_parameter_names() {
+            // Act
+            let res = ParameterizedSegment::parse("{foo-id}");
+
+            // Assert
+            assert_eq!(res, Err(SyntaxError::InvalidParamName('-')));
+        }
+
+        #[test]
+        fn should_parse_dotted_parameter_names() {
+            // Act
+            let res = ParameterizedSegment::parse("{user.id}");
+
+            // Assert
+            assert_eq!(
+                res,
+                Ok(ParameterizedSegment::NamedParam {
+                    name: "user.id".to_owned(),
+                    constraint: None,
+                    optional: false,
+                })
+            );
+        }
+
+        #[test]
+        fn should_reject_empty_components_of_dotted_parameter_names() {
+            // Act
+            let leading = ParameterizedSegment::parse("{.id}");
+            let trailing = ParameterizedSegment::parse("{user.}");
+            let double = ParameterizedSegment::parse("{user..id}");
+
+            // Assert
+            assert_eq!(leading, Err(SyntaxError::InvalidParamName('.')));
+            assert_eq!(trailing, Err(SyntaxError::InvalidParamName('.')));
+            assert_eq!(double, Err(SyntaxError::InvalidParamName('.')));
+        }
+
+        #[test]
+        fn should_reject_invalid_characters_in_static_segment() {
+            // Act
+            let whitespace = ParameterizedSegment::parse("foo bar");
+            let fragment = ParameterizedSegment::parse("foo#bar");
+            let percent = ParameterizedSegment::parse("100%");
+
+            // Assert
+            assert_eq!(whitespace, Err(SyntaxError::InvalidCharacter(' ')));
+            assert_eq!(fragment, Err(SyntaxError::InvalidCharacter('#')));
+            assert_eq!(percent, Err(SyntaxError::InvalidPercentEncoding));
+        }
+    }
+
+    mod match_parts {
+        use super::*;
+
+        fn parts(segment: &str) -> Vec<SegmentPart> {
+            match ParameterizedSegment::parse(segment) {
+                Ok(ParameterizedSegment::Mixed(parts)) => parts,
+                other => panic!("{segment} should be a mixed segment, got {other:?}"),
+            }
+        }
+
+        #[test]
+        fn should_capture_as_much_as_possible() {
+            // Act
+            let captures = match_parts(&parts("{name}.{ext}"), "archive.tar.gz");
+
+            // Assert
+            assert_eq!(
+                captures,
+                Some(vec!["archive.tar".to_owned(), "gz".to_owned()])
+            );
+        }
+
+        #[test]
+        fn should_backtrack_to_satisfy_constraints() {
+            // Act
+            let captures = match_parts(&parts("{name}-{id:int}"), "a-b-12%2D");
+
+            // Assert
+            assert_eq!(captures, None);
+            assert_eq!(
+                match_parts(&parts("{name}-{id:int}"), "a-b-12"),
+                Some(vec!["a-b".to_owned(), "12".to_owned()])
+            );
+        }
+
+        #[test]
+        fn should_not_match_empty_params_or_mismatched_text() {
+            // Act
+            let empty = match_parts(&parts("v{version}"), "v");
+            let mismatched = match_parts(&parts("v{version}"), "x2");
+
+            // Assert
+            assert_eq!(empty, None);
+            assert_eq!(mismatched, None);
+        }
+    }
+
     mod from {
         use super::*;
 
+        #[test]
+        fn should_parse_mixed_segment() {
+            // Act
+            let segment = ParameterizedSegment::try_from("report.{format}").unwrap();
+
+            // Assert
+            assert_eq!(segment.params(), vec!["format"]);
+            assert_eq!(segment.to_template(), "report.{format}");
+        }
+
         #[test]
         fn should_parse_named_parameter() {
             // Act
@@ -143,7 +926,7 @@ mod segment_tests {
 
             // Assert
             assert!(
-                matches!(segment, ParameterizedSegment::NamedParam(param) if param == "named_param")
+                matches!(segment, ParameterizedSegment::NamedParam { name, constraint: None, optional: false } if name == "named_param")
             );
         }
 
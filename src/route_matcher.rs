@@ -0,0 +1,567 @@
+//! A compiled matcher for resolving concrete routes against many
+//! [`ParameterizedRoute`]s at once.
+
+use std::collections::HashMap;
+
+use crate::{
+    ParameterizedRoute,
+    error::WebRouteError,
+    parameterized_route::{
+        constraint::Constraint,
+        segment::{ParameterizedSegment, SegmentPart, match_parts, unnamed_parts},
+    },
+    route_set::RouteConflict,
+    to_segments::ToFixedSegments,
+    utils::declared_query_pairs,
+    web_route::segment::WebSegment,
+};
+
+/// Resolves concrete routes against a set of [`ParameterizedRoute`]s, each
+/// associated with a value of type `T`.
+///
+/// The routes are compiled into a prefix tree of their segments, so the cost
+/// of a lookup depends on the length of the route being matched rather than
+/// on the number of routes. Where more than one route could match, static
+/// segments take priority over mixed segments (e.g. `{name}.{ext}`), then
+/// constrained named parameters, unconstrained named parameters and finally
+/// catch-all parameters.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, RouteMatcher};
+///
+/// let mut matcher = RouteMatcher::new();
+/// matcher.insert(ParameterizedRoute::new("/users/{id}"), "user").unwrap();
+/// matcher.insert(ParameterizedRoute::new("/users/me"), "me").unwrap();
+/// matcher.insert(ParameterizedRoute::new("/files/{*path}"), "file").unwrap();
+///
+/// let matched = matcher.at("/users/123").unwrap();
+/// assert_eq!(*matched.value, "user");
+/// assert_eq!(matched.route, &ParameterizedRoute::new("/users/{id}"));
+/// assert_eq!(matched.params["id"], "123");
+///
+/// assert_eq!(*matcher.at("/users/me").unwrap().value, "me");
+/// assert_eq!(matcher.at("/files/a/b.txt").unwrap().params["path"], "a/b.txt");
+/// assert!(matcher.at("/posts").is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RouteMatcher<T> {
+    root: Node,
+    /// The endpoints of the tree, which its nodes refer to by index. A route
+    /// with optional parameters ends at a node for each of its expansions,
+    /// which all share its endpoint.
+    endpoints: Vec<Endpoint<T>>,
+}
+
+/// The result of successfully matching a concrete route with a
+/// [`RouteMatcher`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteMatch<'a, T> {
+    /// The template that matched.
+    pub route: &'a ParameterizedRoute,
+
+    /// The value associated with the template that matched.
+    pub value: &'a T,
+
+    /// The percent-decoded values captured by each of the template's
+    /// parameters, as well as any of its query parameters that were present.
+    pub params: HashMap<String, String>,
+}
+
+impl<T> RouteMatcher<T> {
+    /// Creates a new, empty [`RouteMatcher`].
+    pub fn new() -> Self {
+        Self {
+            root: Node::default(),
+            endpoints: Vec::new(),
+        }
+    }
+
+    /// Adds `route` to the matcher, associating it with `value`.
+    ///
+    /// A route with optional parameters is added in each of its expanded forms
+    /// (see [`ParameterizedRoute::expand_optional`]), all of which match with
+    /// `route` and `value`.
+    ///
+    /// # Errors
+    ///
+    /// - Any of the errors of [`ParameterizedRoute::validate`].
+    /// - [`WebRouteError::Conflict`] with a [`RouteConflict::Duplicate`] if the
+    ///   same template has already been added, or a
+    ///   [`RouteConflict::Ambiguous`] if a template that matches the same paths
+    ///   (e.g. only differing in the names of its parameters) has.
+    pub fn insert(&mut self, route: ParameterizedRoute, value: T) -> Result<(), WebRouteError> {
+        route.validate()?;
+
+        let expansions = route.expand_optional();
+
+        // All of the expansions are checked before any are added, so that a
+        // conflicting route is not partially added.
+        for expansion in &expansions {
+            if let Some(existing) = *self.root.endpoint_slot(&expansion.to_segments()) {
+                let existing = &self.endpoints[existing];
+                let conflict = if existing.route.to_segments() == route.to_segments() {
+                    RouteConflict::Duplicate(route)
+                } else {
+                    RouteConflict::Ambiguous(existing.route.clone(), route)
+                };
+
+                return Err(conflict.into());
+            }
+        }
+
+        let index = self.endpoints.len();
+        for expansion in &expansions {
+            *self.root.endpoint_slot(&expansion.to_segments()) = Some(index);
+        }
+
+        self.endpoints.push(Endpoint {
+            param_names: route.params(),
+            query_params: route.query_params(),
+            route,
+            value,
+        });
+
+        Ok(())
+    }
+
+    /// Attempts to match a concrete `route` against the templates in the
+    /// matcher.
+    ///
+    /// Returns [`None`] if none of the templates match.
+    pub fn at<R: ToFixedSegments>(&self, route: R) -> Option<RouteMatch<'_, T>> {
+        let segments = route.to_segments();
+        let mut captures = Vec::new();
+        let endpoint = &self.endpoints[self.root.find(&segments, &mut captures)?];
+
+        let mut params = endpoint
+            .param_names
+            .iter()
+            .cloned()
+            .zip(captures)
+            .collect::<HashMap<_, _>>();
+        params.extend(declared_query_pairs(
+            route.to_query().as_deref(),
+            &endpoint.query_params,
+        ));
+
+        Some(RouteMatch {
+            route: &endpoint.route,
+            value: &endpoint.value,
+            params,
+        })
+    }
+
+    /// Returns the number of routes in the matcher.
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Returns `true` if the matcher contains no routes.
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+}
+
+impl<T> Default for RouteMatcher<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A node of the prefix tree, reached by matching a sequence of segments.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    static_children: HashMap<String, Node>,
+    /// The children reached by a mixed segment, keyed by its parts without
+    /// the names of their parameters.
+    mixed_children: Vec<(Vec<SegmentPart>, Node)>,
+    /// The children reached by a named parameter, keyed by its constraint.
+    /// Constrained parameters are ordered before an unconstrained one, so that
+    /// they are tried first.
+    param_children: Vec<(Option<Constraint>, Node)>,
+    /// The index of the endpoint of the route ending in a catch-all parameter
+    /// at this node.
+    catchall: Option<usize>,
+    /// The index of the endpoint of the route ending at this node.
+    endpoint: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Endpoint<T> {
+    route: ParameterizedRoute,
+    /// The names of the route's parameters, in the order their values are
+    /// captured.
+    param_names: Vec<String>,
+    query_params: Vec<String>,
+    value: T,
+}
+
+impl Node {
+    /// Returns the slot for the endpoint of a route with `segments`, creating
+    /// the nodes leading to it if needed.
+    fn endpoint_slot(&mut self, segments: &[ParameterizedSegment]) -> &mut Option<usize> {
+        let mut node = self;
+
+        for segment in segments {
+            match segment {
+                ParameterizedSegment::Static(value) => {
+                    node = node.static_children.entry(value.to_owned()).or_default();
+                }
+                ParameterizedSegment::NamedParam { constraint, .. } => {
+                    node = node.param_child(constraint);
+                }
+                ParameterizedSegment::Mixed(parts) => {
+                    node = node.mixed_child(parts);
+                }
+                ParameterizedSegment::CatchallParam(_) => return &mut node.catchall,
+            }
+        }
+
+        &mut node.endpoint
+    }
+
+    /// Returns the child reached by a mixed segment with `parts`, creating it
+    /// if needed.
+    fn mixed_child(&mut self, parts: &[SegmentPart]) -> &mut Node {
+        let parts = unnamed_parts(parts);
+        let position = match self
+            .mixed_children
+            .iter()
+            .position(|(existing, _)| *existing == parts)
+        {
+            Some(position) => position,
+            None => {
+                self.mixed_children.push((parts, Node::default()));
+                self.mixed_children.len() - 1
+            }
+        };
+
+        &mut self.mixed_children[position].1
+    }
+
+    /// Returns the child reached by a named parameter with `constraint`,
+    /// creating it if needed.
+    fn param_child(&mut self, constraint: &Option<Constraint>) -> &mut Node {
+        let position = match self
+            .param_children
+            .iter()
+            .position(|(existing, _)| existing == constraint)
+        {
+            Some(position) => position,
+            None => {
+                // Unconstrained parameters are tried last.
+                let position = if constraint.is_some() {
+                    self.param_children
+                        .iter()
+                        .position(|(existing, _)| existing.is_none())
+                        .unwrap_or(self.param_children.len())
+                } else {
+                    self.param_children.len()
+                };
+
+                self.param_children
+                    .insert(position, (constraint.clone(), Node::default()));
+                position
+            }
+        };
+
+        &mut self.param_children[position].1
+    }
+
+    /// Finds the index of the endpoint matching the remaining `segments`,
+    /// pushing the decoded value of each parameter onto `captures`.
+    ///
+    /// Falls back to less specific branches if a more specific one does not
+    /// lead to a match.
+    fn find(&self, segments: &[WebSegment], captures: &mut Vec<String>) -> Option<usize> {
+        let Some((segment, remaining_segments)) = segments.split_first() else {
+            return self.endpoint;
+        };
+
+        if let Some(endpoint) = self
+            .static_children
+            .get(segment.as_str())
+            .and_then(|child| child.find(remaining_segments, captures))
+        {
+            return Some(endpoint);
+        }
+
+        for (parts, child) in &self.mixed_children {
+            let Some(values) = match_parts(parts, segment.as_str()) else {
+                continue;
+            };
+
+            let captured = captures.len();
+            captures.extend(values);
+
+            if let Some(endpoint) = child.find(remaining_segments, captures) {
+                return Some(endpoint);
+            }
+
+            captures.truncate(captured);
+        }
+
+        let value = segment.to_decoded();
+        for (constraint, child) in &self.param_children {
+            if constraint
+                .as_ref()
+                .is_some_and(|constraint| !constraint.is_satisfied_by(&value))
+            {
+                continue;
+            }
+
+            captures.push(value.clone());
+
+            if let Some(endpoint) = child.find(remaining_segments, captures) {
+                return Some(endpoint);
+            }
+
+            captures.pop();
+        }
+
+        let endpoint = self.catchall?;
+        captures.push(
+            segments
+                .iter()
+                .map(WebSegment::to_decoded)
+                .collect::<Vec<_>>()
+                .join("/"),
+        );
+
+        Some(endpoint)
+    }
+}
+
+#[cfg(test)]
+mod route_matcher_tests {
+    use super::*;
+
+    fn matcher(routes: &[&'static str]) -> RouteMatcher<&'static str> {
+        let mut matcher = RouteMatcher::new();
+        for route in routes {
+            matcher
+                .insert(ParameterizedRoute::new(*route), *route)
+                .expect("routes should not conflict");
+        }
+
+        matcher
+    }
+
+    mod at {
+        use super::*;
+
+        #[test]
+        fn should_prefer_static_over_param_over_catchall() {
+            // Arrange
+            let matcher = matcher(&["/files/{*path}", "/files/{id}", "/files/new"]);
+
+            // Act
+            let static_match = matcher.at("/files/new").unwrap();
+            let param_match = matcher.at("/files/123").unwrap();
+            let catchall_match = matcher.at("/files/a/b").unwrap();
+
+            // Assert
+            assert_eq!(*static_match.value, "/files/new");
+            assert_eq!(*param_match.value, "/files/{id}");
+            assert_eq!(*catchall_match.value, "/files/{*path}");
+        }
+
+        #[test]
+        fn should_prefer_constrained_param() {
+            // Arrange
+            let matcher = matcher(&["/users/{name}", "/users/{id:int}"]);
+
+            // Act
+            let constrained_match = matcher.at("/users/123").unwrap();
+            let unconstrained_match = matcher.at("/users/me").unwrap();
+
+            // Assert
+            assert_eq!(*constrained_match.value, "/users/{id:int}");
+            assert_eq!(*unconstrained_match.value, "/users/{name}");
+        }
+
+        #[test]
+        fn should_prefer_mixed_segment_over_param() {
+            // Arrange
+            let matcher = matcher(&["/files/{id}", "/files/{name}.{ext}"]);
+
+            // Act
+            let mixed_match = matcher.at("/files/archive.tar.gz").unwrap();
+            let param_match = matcher.at("/files/123").unwrap();
+
+            // Assert
+            assert_eq!(*mixed_match.value, "/files/{name}.{ext}");
+            assert_eq!(
+                mixed_match.params,
+                HashMap::from([
+                    ("name".to_owned(), "archive.tar".to_owned()),
+                    ("ext".to_owned(), "gz".to_owned()),
+                ])
+            );
+            assert_eq!(*param_match.value, "/files/{id}");
+        }
+
+        #[test]
+        fn should_backtrack_when_more_specific_branch_does_not_match() {
+            // Arrange
+            let matcher = matcher(&["/users/me/settings", "/users/{id}/posts"]);
+
+            // Act
+            let matched = matcher.at("/users/me/posts").unwrap();
+
+            // Assert
+            assert_eq!(*matched.value, "/users/{id}/posts");
+            assert_eq!(
+                matched.params,
+                HashMap::from([("id".to_owned(), "me".to_owned())])
+            );
+        }
+
+        #[test]
+        fn should_name_params_per_route() {
+            // Arrange
+            let matcher = matcher(&["/users/{user_id}/posts", "/users/{id}"]);
+
+            // Act
+            let posts = matcher.at("/users/1/posts").unwrap();
+            let user = matcher.at("/users/2").unwrap();
+
+            // Assert
+            assert_eq!(posts.params["user_id"], "1");
+            assert_eq!(user.params["id"], "2");
+        }
+
+        #[test]
+        fn should_decode_params_and_capture_query_params() {
+            // Arrange
+            let mut matcher = RouteMatcher::new();
+            matcher
+                .insert(ParameterizedRoute::new("/files/{*path}{?download}"), ())
+                .unwrap();
+
+            // Act
+            let matched = matcher.at("/files/a%20b/c?download=true&other=1").unwrap();
+
+            // Assert
+            assert_eq!(
+                matched.params,
+                HashMap::from([
+                    ("path".to_owned(), "a b/c".to_owned()),
+                    ("download".to_owned(), "true".to_owned()),
+                ])
+            );
+        }
+
+        #[test]
+        fn should_not_match_catchall_without_segments() {
+            // Arrange
+            let matcher = matcher(&["/files/{*path}"]);
+
+            // Act
+            let matched = matcher.at("/files");
+
+            // Assert
+            assert!(matched.is_none());
+        }
+
+        #[test]
+        fn should_match_root() {
+            // Arrange
+            let matcher = matcher(&["/", "/{id}"]);
+
+            // Act
+            let matched = matcher.at("/").unwrap();
+
+            // Assert
+            assert_eq!(*matched.value, "/");
+        }
+    }
+
+    mod insert {
+        use super::*;
+
+        #[test]
+        fn should_match_each_expansion_of_optional_params() {
+            // Arrange
+            let matcher = matcher(&["/reports/{year?:int}/{month?}"]);
+
+            // Act
+            let none = matcher.at("/reports").unwrap();
+            let year = matcher.at("/reports/2024").unwrap();
+            let both = matcher.at("/reports/2024/05").unwrap();
+
+            // Assert
+            assert_eq!(matcher.len(), 1);
+            assert_eq!(
+                none.route,
+                &ParameterizedRoute::new("/reports/{year?:int}/{month?}")
+            );
+            assert!(none.params.is_empty());
+            assert_eq!(
+                year.params,
+                HashMap::from([("year".to_owned(), "2024".to_owned())])
+            );
+            assert_eq!(both.params["month"], "05");
+            assert!(matcher.at("/reports/latest").is_none());
+        }
+
+        #[test]
+        fn should_not_partially_add_conflicting_optional_params() {
+            // Arrange
+            let mut matcher = matcher(&["/reports/{year}"]);
+
+            // Act
+            let res = matcher.insert(ParameterizedRoute::new("/reports/{year?}"), "");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::Conflict(RouteConflict::Ambiguous(_, _)))
+            ));
+            assert_eq!(matcher.len(), 1);
+            assert!(matcher.at("/reports").is_none());
+        }
+
+        #[test]
+        fn should_report_duplicate() {
+            // Arrange
+            let mut matcher = matcher(&["/users/{id}"]);
+
+            // Act
+            let res = matcher.insert(ParameterizedRoute::new("/users/{id}{?page}"), "");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::Conflict(RouteConflict::Duplicate(_)))
+            ));
+            assert_eq!(matcher.len(), 1);
+        }
+
+        #[test]
+        fn should_report_differently_named_params_as_ambiguous() {
+            // Arrange
+            let mut matcher = matcher(&["/users/{id}"]);
+
+            // Act
+            let res = matcher.insert(ParameterizedRoute::new("/users/{name}"), "");
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::Conflict(RouteConflict::Ambiguous(_, _)))
+            ));
+        }
+
+        #[test]
+        fn should_reject_invalid_route() {
+            // Act
+            let res = RouteMatcher::new().insert(ParameterizedRoute::new("/{*rest}/more"), ());
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::MisplacedCatchall(_))));
+        }
+    }
+}
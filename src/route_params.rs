@@ -0,0 +1,441 @@
+//! Defines what can be used to populate the parameters of a
+//! [`ParameterizedRoute`](crate::ParameterizedRoute).
+
+use std::{
+    borrow::{Borrow, Cow},
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::{BuildHasher, Hash},
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::WebRouteError,
+    route_segment::ToRouteSegment,
+    utils::{ParamValue, serialized_param, serialized_param_names},
+};
+
+/// Values that the parameters of a
+/// [`ParameterizedRoute`](crate::ParameterizedRoute) can be populated with,
+/// looked up by parameter name.
+///
+/// It is implemented for:
+///
+/// - References to anything (sized) that implements `serde::Serialize` into
+///   key-value pairs (e.g. `&MyParams` or `&HashMap<_, _>`). Dotted parameter
+///   names (e.g. `{user.id}`) look up the fields of nested structs and maps,
+///   and sequences populate catch-all parameters segment by segment.
+/// - [`HashMap`]s and [`BTreeMap`]s, arrays, [`Vec`]s and slices (e.g.
+///   `&[("id", 1)][..]`) of `(key, value)` pairs, and a single `(key, value)`
+///   pair, whose values implement
+///   [`ToRouteSegment`] (e.g. anything that implements
+///   [`Display`](fmt::Display)). These are looked up directly, without
+///   serializing anything.
+/// - Closures, via [`from_fn`].
+/// - Structs deriving `RouteParams` (with the `macros` feature), and
+///   references to them, whose fields are looked up directly.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, route_params};
+///
+/// let route = ParameterizedRoute::new("/users/{user_id}/posts/{post_id}");
+///
+/// let from_pairs = route.to_web_route([("user_id", 123), ("post_id", 456)]).unwrap();
+/// let from_fn = route
+///     .to_web_route(route_params::from_fn(|param| match param {
+///         "user_id" => Some("123".to_owned()),
+///         "post_id" => Some("456".to_owned()),
+///         _ => None,
+///     }))
+///     .unwrap();
+///
+/// assert_eq!(&*from_pairs, "/users/123/posts/456");
+/// assert_eq!(from_fn, from_pairs);
+/// ```
+pub trait RouteParams {
+    /// Returns the (unencoded) value of the parameter `name`, or [`None`] if
+    /// it has no value.
+    ///
+    /// # Errors
+    ///
+    /// A [`WebRouteError`] if values cannot be looked up by name (e.g.
+    /// [`WebRouteError::InvalidValue`] if a serialized value is not made up of
+    /// key-value pairs).
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError>;
+
+    /// Returns the (unencoded) segments of the value of the catch-all
+    /// parameter `name`, or [`None`] if it has no value.
+    ///
+    /// By default, the value returned by [`RouteParams::param`] is split on
+    /// `/`, ignoring empty segments.
+    ///
+    /// # Errors
+    ///
+    /// A [`WebRouteError`] if values cannot be looked up by name, as with
+    /// [`RouteParams::param`].
+    fn catchall_param(&self, name: &str) -> Result<Option<Vec<String>>, WebRouteError> {
+        Ok(self.param(name)?.map(|value| split_catchall(&value)))
+    }
+
+    /// Returns the names of all of the values, which
+    /// [`ParameterizedRoute::to_web_route_strict`](crate::ParameterizedRoute::to_web_route_strict)
+    /// checks against the parameters of the route.
+    ///
+    /// By default, there are no names, as values looked up by a closure cannot
+    /// be listed.
+    ///
+    /// # Errors
+    ///
+    /// A [`WebRouteError`] if the names cannot be listed, as with
+    /// [`RouteParams::param`].
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(Vec::new())
+    }
+}
+
+// Not implemented for unsized types, so that slices of pairs can be looked up
+// directly.
+impl<T: Serialize> RouteParams for &T {
+    /// Sequences have no value as a single parameter.
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        match serialized_param(*self, name)? {
+            Some(ParamValue::Scalar(value)) => Ok(Some(Cow::Owned(value))),
+            Some(ParamValue::Sequence(_)) | None => Ok(None),
+        }
+    }
+
+    /// Each element of a sequence is a segment, which may contain `/`.
+    fn catchall_param(&self, name: &str) -> Result<Option<Vec<String>>, WebRouteError> {
+        match serialized_param(*self, name)? {
+            Some(ParamValue::Scalar(value)) => Ok(Some(split_catchall(&value))),
+            Some(ParamValue::Sequence(segments)) => Ok(Some(segments)),
+            None => Ok(None),
+        }
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        serialized_param_names(*self)
+    }
+}
+
+impl<K, V, S> RouteParams for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: ToRouteSegment,
+    S: BuildHasher,
+{
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok(self
+            .get(name)
+            .map(|value| Cow::Owned(value.to_route_segment())))
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(self.keys().map(|key| key.borrow().to_owned()).collect())
+    }
+}
+
+impl<K, V> RouteParams for BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: ToRouteSegment,
+{
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok(self
+            .get(name)
+            .map(|value| Cow::Owned(value.to_route_segment())))
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(self.keys().map(|key| key.borrow().to_owned()).collect())
+    }
+}
+
+impl<K: AsRef<str>, V: ToRouteSegment> RouteParams for (K, V) {
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok(find_pair(std::slice::from_ref(self), name))
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(pair_names(std::slice::from_ref(self)))
+    }
+}
+
+impl<K: AsRef<str>, V: ToRouteSegment, const N: usize> RouteParams for [(K, V); N] {
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok(find_pair(self, name))
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(pair_names(self))
+    }
+}
+
+impl<K: AsRef<str>, V: ToRouteSegment> RouteParams for Vec<(K, V)> {
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok(find_pair(self, name))
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(pair_names(self))
+    }
+}
+
+impl<K: AsRef<str>, V: ToRouteSegment> RouteParams for &[(K, V)] {
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok(find_pair(self, name))
+    }
+
+    fn param_names(&self) -> Result<Vec<String>, WebRouteError> {
+        Ok(pair_names(self))
+    }
+}
+
+/// Route parameters whose values are looked up by a closure. Created by
+/// [`from_fn`].
+#[derive(Clone, Copy)]
+pub struct FromFn<F>(F);
+
+impl<F: Fn(&str) -> Option<String>> RouteParams for FromFn<F> {
+    fn param(&self, name: &str) -> Result<Option<Cow<'_, str>>, WebRouteError> {
+        Ok((self.0)(name).map(Cow::Owned))
+    }
+}
+
+impl<F> fmt::Debug for FromFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromFn").finish_non_exhaustive()
+    }
+}
+
+/// Creates [`RouteParams`] that look up the value of each parameter by
+/// calling `f` with its name.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, route_params};
+///
+/// let route = ParameterizedRoute::new("/users/{user_id}");
+/// let user_id = 123;
+///
+/// let web_route = route
+///     .to_web_route(route_params::from_fn(|_| Some(user_id.to_string())))
+///     .unwrap();
+///
+/// assert_eq!(&*web_route, "/users/123");
+/// ```
+pub fn from_fn<F: Fn(&str) -> Option<String>>(f: F) -> FromFn<F> {
+    FromFn(f)
+}
+
+/// Splits the value of a catch-all parameter into its non-empty segments.
+fn split_catchall(value: &str) -> Vec<String> {
+    value
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Returns the value of the last of the `pairs` whose key is `name`, as with
+/// the serialized values.
+fn find_pair<K: AsRef<str>, V: ToRouteSegment>(
+    pairs: &[(K, V)],
+    name: &str,
+) -> Option<Cow<'static, str>> {
+    pairs
+        .iter()
+        .rev()
+        .find(|(key, _)| key.as_ref() == name)
+        .map(|(_, value)| Cow::Owned(value.to_route_segment()))
+}
+
+/// Returns the keys of the `pairs`.
+fn pair_names<K: AsRef<str>, V>(pairs: &[(K, V)]) -> Vec<String> {
+    pairs
+        .iter()
+        .map(|(key, _)| key.as_ref().to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod route_params_tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Params {
+        id: u32,
+        name: Option<String>,
+        ratio: f64,
+        tags: Vec<String>,
+        kind: Kind,
+    }
+
+    #[derive(serde::Serialize)]
+    enum Kind {
+        #[serde(rename = "admin")]
+        Admin,
+    }
+
+    fn params() -> Params {
+        Params {
+            id: 42,
+            name: None,
+            ratio: 1.0,
+            tags: vec!["a".to_owned()],
+            kind: Kind::Admin,
+        }
+    }
+
+    #[test]
+    fn should_look_up_serialized_struct_fields() {
+        // Arrange
+        let params = params();
+        let values = &params;
+
+        // Act
+        let id = values.param("id").unwrap();
+        let ratio = values.param("ratio").unwrap();
+        let kind = values.param("kind").unwrap();
+
+        // Assert
+        assert_eq!(id.as_deref(), Some("42"));
+        assert_eq!(ratio.as_deref(), Some("1.0"));
+        assert_eq!(kind.as_deref(), Some("admin"));
+    }
+
+    #[test]
+    fn should_have_no_value_for_none_sequences_and_missing_fields() {
+        // Arrange
+        let params = params();
+        let values = &params;
+
+        // Act
+        let name = values.param("name").unwrap();
+        let tags = values.param("tags").unwrap();
+        let missing = values.param("missing").unwrap();
+
+        // Assert
+        assert_eq!(name, None);
+        assert_eq!(tags, None);
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn should_look_up_dotted_names_in_nested_values() {
+        // Arrange
+        #[derive(serde::Serialize)]
+        struct Nested {
+            params: Params,
+        }
+
+        let nested = Nested { params: params() };
+        let values = &nested;
+
+        // Act
+        let id = values.param("params.id").unwrap();
+        let kind = values.param("params.kind").unwrap();
+        let missing = values.param("params.id.missing").unwrap();
+
+        // Assert
+        assert_eq!(id.as_deref(), Some("42"));
+        assert_eq!(kind.as_deref(), Some("admin"));
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn should_split_catchall_values_into_segments() {
+        // Arrange
+        let params = params();
+        let values = &params;
+        let pair = ("path", "/a//b/");
+
+        // Act
+        let from_sequence = values.catchall_param("tags").unwrap();
+        let from_scalar = values.catchall_param("id").unwrap();
+        let from_pair = pair.catchall_param("path").unwrap();
+
+        // Assert
+        assert_eq!(from_sequence, Some(vec!["a".to_owned()]));
+        assert_eq!(from_scalar, Some(vec!["42".to_owned()]));
+        assert_eq!(from_pair, Some(vec!["a".to_owned(), "b".to_owned()]));
+    }
+
+    #[test]
+    fn should_list_the_names_of_values() {
+        // Arrange
+        let params = params();
+        let values = &params;
+        let pairs = [("b", 1), ("a", 2)];
+
+        // Act
+        let serialized = values.param_names().unwrap();
+        let serialized_pairs = (&&pairs).param_names().unwrap();
+        let from_pairs = pairs.param_names().unwrap();
+        let from_fn = from_fn(|_| None).param_names().unwrap();
+
+        // Assert
+        assert_eq!(serialized, vec!["id", "kind", "name", "ratio", "tags"]);
+        assert_eq!(serialized_pairs, vec!["b", "a"]);
+        assert_eq!(from_pairs, vec!["b", "a"]);
+        assert!(from_fn.is_empty());
+    }
+
+    #[test]
+    fn should_look_up_serialized_maps_and_pairs() {
+        // Arrange
+        // The references are serialized, rather than being looked up directly.
+        let map = &HashMap::from([("id", 1)]);
+        let pairs = &vec![("id", "a"), ("id", "b")];
+
+        // Act
+        let from_map = (&map).param("id").unwrap();
+        let from_pairs = (&pairs).param("id").unwrap();
+
+        // Assert
+        assert_eq!(from_map.as_deref(), Some("1"));
+        assert_eq!(from_pairs.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn should_reject_values_that_are_not_key_value_pairs() {
+        // Act
+        let res = (&"not key-value pairs").param("id");
+
+        // Assert
+        assert!(matches!(res, Err(WebRouteError::InvalidValue)));
+    }
+
+    #[test]
+    fn should_look_up_display_values_without_serializing() {
+        // Arrange
+        struct UserId(u32);
+
+        impl fmt::Display for UserId {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "user-{}", self.0)
+            }
+        }
+
+        let map = BTreeMap::from([("id", UserId(1))]);
+        let pair = ("id", UserId(2));
+        let pairs = [("id", UserId(3)), ("name", UserId(4))];
+        let slice = &pairs[..];
+
+        // Act
+        let from_map = map.param("id").unwrap();
+        let from_pair = pair.param("id").unwrap();
+        let from_slice = slice.param("id").unwrap();
+        let slice_names = slice.param_names().unwrap();
+
+        // Assert
+        assert_eq!(from_map.as_deref(), Some("user-1"));
+        assert_eq!(from_pair.as_deref(), Some("user-2"));
+        assert_eq!(from_slice.as_deref(), Some("user-3"));
+        assert_eq!(slice_names, vec!["id", "name"]);
+    }
+}
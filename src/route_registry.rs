@@ -0,0 +1,349 @@
+//! A registry of named [`ParameterizedRoute`]s, for referring to routes by
+//! name rather than by holding on to them.
+
+use std::collections::{BTreeMap, btree_map};
+
+use crate::{ParameterizedRoute, RouteParams, WebRoute, error::WebRouteError};
+
+/// Maps names (e.g. `"user.profile"`) to [`ParameterizedRoute`]s, so that
+/// templates, emails and redirects can populate a route by its name (i.e.
+/// reverse routing).
+///
+/// Registries built for nested routers can be nested under a prefix, which
+/// prefixes both the names and the routes of their entries.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, RouteRegistry};
+///
+/// let mut users = RouteRegistry::new();
+/// users
+///     .insert("profile", ParameterizedRoute::new("/{user_id}"))
+///     .unwrap();
+///
+/// let mut routes = RouteRegistry::new();
+/// routes.insert("home", ParameterizedRoute::new("/")).unwrap();
+/// routes
+///     .nest("user", &ParameterizedRoute::new("/users"), users)
+///     .unwrap();
+///
+/// assert_eq!(
+///     &*routes.url_for("user.profile", [("user_id", 42)]).unwrap(),
+///     "/users/42"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteRegistry {
+    routes: BTreeMap<String, ParameterizedRoute>,
+}
+
+impl RouteRegistry {
+    /// Creates a new, empty [`RouteRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `route` under `name`.
+    ///
+    /// # Errors
+    ///
+    /// [`WebRouteError::DuplicateRouteName`] if a route is already registered
+    /// under `name`. `route` is not registered.
+    pub fn insert<N: Into<String>>(
+        &mut self,
+        name: N,
+        route: ParameterizedRoute,
+    ) -> Result<(), WebRouteError> {
+        match self.routes.entry(name.into()) {
+            btree_map::Entry::Occupied(entry) => {
+                Err(WebRouteError::DuplicateRouteName(entry.key().to_owned()))
+            }
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(route);
+                Ok(())
+            }
+        }
+    }
+
+    /// Registers every route of `registry`, with its name prefixed by
+    /// `name_prefix` and a `.` (e.g. `"profile"` becomes `"user.profile"`),
+    /// and `route_prefix` joined in front of its route (see
+    /// [`ParameterizedRoute::try_join`]).
+    ///
+    /// # Errors
+    ///
+    /// None of the routes of `registry` are registered if any of them cannot
+    /// be nested:
+    ///
+    /// - Any of the errors of [`ParameterizedRoute::try_join`] (e.g.
+    ///   [`WebRouteError::DuplicateParams`] if a route reuses a parameter name
+    ///   of `route_prefix`).
+    /// - [`WebRouteError::DuplicateRouteName`] with the first of the prefixed
+    ///   names that a route is already registered under.
+    pub fn nest(
+        &mut self,
+        name_prefix: &str,
+        route_prefix: &ParameterizedRoute,
+        registry: RouteRegistry,
+    ) -> Result<(), WebRouteError> {
+        let nested_routes = registry
+            .routes
+            .into_iter()
+            .map(|(name, route)| {
+                Ok((
+                    format!("{name_prefix}.{name}"),
+                    route_prefix.try_join(route)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, WebRouteError>>()?;
+
+        if let Some((name, _)) = nested_routes
+            .iter()
+            .find(|(name, _)| self.routes.contains_key(name))
+        {
+            return Err(WebRouteError::DuplicateRouteName(name.to_owned()));
+        }
+
+        self.routes.extend(nested_routes);
+
+        Ok(())
+    }
+
+    /// Returns the route registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&ParameterizedRoute> {
+        self.routes.get(name)
+    }
+
+    /// Populates the route registered under `name` with `values` (see
+    /// [`ParameterizedRoute::to_web_route`]).
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::UnknownRouteName`] if no route is registered under
+    ///   `name`.
+    /// - Any of the errors of [`ParameterizedRoute::to_web_route`] (e.g.
+    ///   [`WebRouteError::MismatchedParams`] listing every parameter that has
+    ///   no value).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, RouteRegistry, error::WebRouteError};
+    ///
+    /// let mut routes = RouteRegistry::new();
+    /// routes
+    ///     .insert("post", ParameterizedRoute::new("/posts/{post_id}"))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     &*routes.url_for("post", [("post_id", 7)]).unwrap(),
+    ///     "/posts/7"
+    /// );
+    /// assert!(matches!(
+    ///     routes.url_for("posts", [("post_id", 7)]),
+    ///     Err(WebRouteError::UnknownRouteName(_))
+    /// ));
+    /// ```
+    pub fn url_for<P: RouteParams>(
+        &self,
+        name: &str,
+        values: P,
+    ) -> Result<WebRoute, WebRouteError> {
+        self.get(name)
+            .ok_or_else(|| WebRouteError::UnknownRouteName(name.to_owned()))?
+            .to_web_route(values)
+    }
+
+    /// Returns an iterator over the names and routes, ordered by name.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, ParameterizedRoute> {
+        self.routes.iter()
+    }
+
+    /// Returns the number of routes in the registry.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Returns `true` if the registry contains no routes.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl IntoIterator for RouteRegistry {
+    type Item = (String, ParameterizedRoute);
+    type IntoIter = btree_map::IntoIter<String, ParameterizedRoute>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.routes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a RouteRegistry {
+    type Item = (&'a String, &'a ParameterizedRoute);
+    type IntoIter = btree_map::Iter<'a, String, ParameterizedRoute>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod route_registry_tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn user_routes() -> RouteRegistry {
+        let mut users = RouteRegistry::new();
+        users
+            .insert("profile", ParameterizedRoute::new("/{user_id}"))
+            .unwrap();
+        users
+            .insert("posts", ParameterizedRoute::new("/{user_id}/posts{?page}"))
+            .unwrap();
+
+        users
+    }
+
+    mod insert {
+        use super::*;
+
+        #[test]
+        fn should_reject_duplicate_names() {
+            // Arrange
+            let mut routes = RouteRegistry::new();
+            routes.insert("home", ParameterizedRoute::new("/")).unwrap();
+
+            // Act
+            let res = routes.insert("home", ParameterizedRoute::new("/home"));
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::DuplicateRouteName(name)) if name == "home"));
+            assert_eq!(routes.get("home"), Some(&ParameterizedRoute::new("/")));
+        }
+    }
+
+    mod nest {
+        use super::*;
+
+        #[test]
+        fn should_prefix_names_and_routes() {
+            // Arrange
+            let mut routes = RouteRegistry::new();
+
+            // Act
+            routes
+                .nest(
+                    "org.user",
+                    &ParameterizedRoute::new("/orgs/{org_id}/users"),
+                    user_routes(),
+                )
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                routes.iter().collect::<Vec<_>>(),
+                vec![
+                    (
+                        &"org.user.posts".to_owned(),
+                        &ParameterizedRoute::new("/orgs/{org_id}/users/{user_id}/posts{?page}")
+                    ),
+                    (
+                        &"org.user.profile".to_owned(),
+                        &ParameterizedRoute::new("/orgs/{org_id}/users/{user_id}")
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn should_not_register_any_routes_if_a_name_is_taken() {
+            // Arrange
+            let mut routes = RouteRegistry::new();
+            routes
+                .insert("user.profile", ParameterizedRoute::new("/me"))
+                .unwrap();
+
+            // Act
+            let res = routes.nest("user", &ParameterizedRoute::new("/users"), user_routes());
+
+            // Assert
+            assert!(
+                matches!(res, Err(WebRouteError::DuplicateRouteName(name)) if name == "user.profile")
+            );
+            assert_eq!(routes.len(), 1);
+        }
+
+        #[test]
+        fn should_not_register_any_routes_if_a_param_is_reused() {
+            // Arrange
+            let mut routes = RouteRegistry::new();
+
+            // Act
+            let res = routes.nest(
+                "user",
+                &ParameterizedRoute::new("/users/{user_id}"),
+                user_routes(),
+            );
+
+            // Assert
+            assert!(
+                matches!(res, Err(WebRouteError::DuplicateParams(params)) if params == vec!["user_id"])
+            );
+            assert!(routes.is_empty());
+        }
+    }
+
+    mod url_for {
+        use super::*;
+
+        fn routes() -> RouteRegistry {
+            let mut routes = RouteRegistry::new();
+            routes
+                .nest("user", &ParameterizedRoute::new("/users"), user_routes())
+                .unwrap();
+
+            routes
+        }
+
+        #[test]
+        fn should_populate_named_route() {
+            // Arrange
+            let values = HashMap::from([("user_id", "42"), ("page", "2")]);
+
+            // Act
+            let profile = routes().url_for("user.profile", &values);
+            let posts = routes().url_for("user.posts", &values);
+
+            // Assert
+            assert_eq!(profile.unwrap(), WebRoute::new("/users/42"));
+            assert_eq!(posts.unwrap(), WebRoute::new("/users/42/posts?page=2"));
+        }
+
+        #[test]
+        fn should_report_unknown_names() {
+            // Act
+            let res = routes().url_for("user.settings", [("user_id", 42)]);
+
+            // Assert
+            assert!(
+                matches!(res, Err(WebRouteError::UnknownRouteName(name)) if name == "user.settings")
+            );
+        }
+
+        #[test]
+        fn should_report_missing_params() {
+            // Act
+            let res = routes().url_for("user.profile", [("id", 42)]);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(WebRouteError::MismatchedParams { template, missing, .. })
+                    if template == "/users/{user_id}" && missing == vec!["user_id"]
+            ));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Defines how values are represented when they populate a parameter of a
+//! [`ParameterizedRoute`](crate::ParameterizedRoute).
+
+use std::fmt;
+
+/// A value that can populate a parameter of a
+/// [`ParameterizedRoute`](crate::ParameterizedRoute).
+///
+/// It is implemented for everything that implements
+/// [`Display`](fmt::Display), and can be implemented for types that do not
+/// (e.g. because they have no single human-readable representation).
+///
+/// The value is percent-encoded when it populates a route, so it should not
+/// be encoded already.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, ToRouteSegment};
+///
+/// struct Date {
+///     year: u16,
+///     month: u8,
+///     day: u8,
+/// }
+///
+/// impl ToRouteSegment for Date {
+///     fn to_route_segment(&self) -> String {
+///         format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+///     }
+/// }
+///
+/// let route = ParameterizedRoute::new("/reports/{date}");
+/// let date = Date { year: 2024, month: 3, day: 9 };
+///
+/// let web_route = route.to_web_route([("date", date)]).unwrap();
+///
+/// assert_eq!(&*web_route, "/reports/2024-03-09");
+/// ```
+pub trait ToRouteSegment {
+    /// Returns the (unencoded) value of the parameter.
+    fn to_route_segment(&self) -> String;
+}
+
+impl<T: fmt::Display + ?Sized> ToRouteSegment for T {
+    fn to_route_segment(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod route_segment_tests {
+    use std::collections::HashMap;
+
+    use crate::ParameterizedRoute;
+
+    use super::*;
+
+    /// A value with no `Display` implementation.
+    struct Coordinates {
+        lat: f32,
+        lng: f32,
+    }
+
+    impl ToRouteSegment for Coordinates {
+        fn to_route_segment(&self) -> String {
+            format!("{}_{}", self.lat, self.lng)
+        }
+    }
+
+    #[test]
+    fn should_populate_from_display_and_custom_values() {
+        // Arrange
+        let route = ParameterizedRoute::new("/places/{coordinates}");
+        let custom = HashMap::from([(
+            "coordinates",
+            Coordinates {
+                lat: 1.5,
+                lng: -2.0,
+            },
+        )]);
+        let display = [("coordinates", 'x')];
+
+        // Act
+        let from_custom = route.to_web_route(custom).unwrap();
+        let from_display = route.to_web_route(display).unwrap();
+
+        // Assert
+        assert_eq!(&*from_custom, "/places/1.5_-2");
+        assert_eq!(&*from_display, "/places/x");
+    }
+
+    #[test]
+    fn should_serialize_fields_by_their_route_segment() {
+        // Arrange
+        #[derive(serde::Serialize)]
+        struct Params {
+            #[serde(serialize_with = "crate::display::serialize")]
+            coordinates: Coordinates,
+        }
+
+        let route = ParameterizedRoute::new("/places/{coordinates}");
+        let params = Params {
+            coordinates: Coordinates { lat: 0.5, lng: 3.0 },
+        };
+
+        // Act
+        let web_route = route.to_web_route(&params).unwrap();
+
+        // Assert
+        assert_eq!(&*web_route, "/places/0.5_3");
+    }
+}
@@ -0,0 +1,577 @@
+//! A collection of [`ParameterizedRoute`]s that are checked against each other
+//! as they are added.
+
+use crate::{
+    ParameterizedRoute,
+    parameterized_route::{
+        constraint::Constraint,
+        segment::{ParameterizedSegment, SegmentPart, match_parts, unnamed_parts},
+    },
+    utils::decode_segment,
+};
+
+/// Describes how a route conflicts with one that is already in a [`RouteSet`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RouteConflict {
+    /// The exact same path template is defined more than once.
+    #[error("route `{0}` is defined more than once")]
+    Duplicate(ParameterizedRoute),
+
+    /// The routes can both match the same path, and neither is more specific
+    /// than the other (e.g. `/users/{id}` and `/users/{name}`).
+    #[error("routes `{0}` and `{1}` are ambiguous, they can both match the same path")]
+    Ambiguous(ParameterizedRoute, ParameterizedRoute),
+
+    /// Every path matched by `route` is also matched by the more general `by`
+    /// (e.g. `/files/new` is shadowed by `/files/{*path}`).
+    #[error("route `{route}` is shadowed by `{by}`")]
+    Shadowed {
+        /// The more specific route.
+        route: ParameterizedRoute,
+        /// The more general route.
+        by: ParameterizedRoute,
+    },
+}
+
+/// A collection of [`ParameterizedRoute`]s that do not conflict with each
+/// other.
+///
+/// Only the path templates of the routes are compared, any query parameters
+/// are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, RouteSet, route_set::RouteConflict};
+///
+/// let mut routes = RouteSet::new();
+/// routes.insert(ParameterizedRoute::new("/users/{id}")).unwrap();
+/// routes.insert(ParameterizedRoute::new("/users/{id}/posts")).unwrap();
+///
+/// assert_eq!(
+///     routes.insert(ParameterizedRoute::new("/users/{name}")),
+///     Err(RouteConflict::Ambiguous(
+///         ParameterizedRoute::new("/users/{id}"),
+///         ParameterizedRoute::new("/users/{name}"),
+///     ))
+/// );
+/// assert_eq!(routes.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteSet {
+    routes: Vec<ParameterizedRoute>,
+}
+
+impl RouteSet {
+    /// Creates a new, empty [`RouteSet`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`RouteSet`] from `routes`, reporting every conflict rather
+    /// than just the first.
+    ///
+    /// # Errors
+    ///
+    /// Every [`RouteConflict`] between `routes`, in the order they were found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{ParameterizedRoute, RouteSet};
+    ///
+    /// let conflicts = RouteSet::try_from_routes([
+    ///     ParameterizedRoute::new("/files/{*path}"),
+    ///     ParameterizedRoute::new("/files/new"),
+    ///     ParameterizedRoute::new("/files/{*path}"),
+    /// ])
+    /// .unwrap_err();
+    ///
+    /// assert_eq!(conflicts.len(), 2);
+    /// ```
+    pub fn try_from_routes<I>(routes: I) -> Result<Self, Vec<RouteConflict>>
+    where
+        I: IntoIterator<Item = ParameterizedRoute>,
+    {
+        let mut route_set = Self::new();
+        let conflicts = routes
+            .into_iter()
+            .filter_map(|route| route_set.insert(route).err())
+            .collect::<Vec<_>>();
+
+        if conflicts.is_empty() {
+            Ok(route_set)
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    /// Adds `route` to the set, unless it conflicts with a route that is
+    /// already in it.
+    ///
+    /// # Errors
+    ///
+    /// The [`RouteConflict`] with the first route in the set that `route`
+    /// conflicts with. `route` is not added.
+    pub fn insert(&mut self, route: ParameterizedRoute) -> Result<(), RouteConflict> {
+        if let Some(conflict) = self
+            .routes
+            .iter()
+            .find_map(|existing| find_conflict(existing, &route))
+        {
+            return Err(conflict);
+        }
+
+        self.routes.push(route);
+
+        Ok(())
+    }
+
+    /// Returns `true` if the set contains a route with the same path template
+    /// as `route`.
+    pub fn contains(&self, route: &ParameterizedRoute) -> bool {
+        self.routes.iter().any(|existing| **existing == **route)
+    }
+
+    /// Returns an iterator over the routes, in the order they were added.
+    pub fn iter(&self) -> std::slice::Iter<'_, ParameterizedRoute> {
+        self.routes.iter()
+    }
+
+    /// Returns the number of routes in the set.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Returns `true` if the set contains no routes.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl IntoIterator for RouteSet {
+    type Item = ParameterizedRoute;
+    type IntoIter = std::vec::IntoIter<ParameterizedRoute>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.routes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a RouteSet {
+    type Item = &'a ParameterizedRoute;
+    type IntoIter = std::slice::Iter<'a, ParameterizedRoute>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// How two overlapping routes relate to each other.
+#[derive(Debug, Default)]
+struct Overlap {
+    /// The first route has a parameter where the second has a more specific
+    /// segment.
+    first_is_general: bool,
+    /// The second route has a parameter where the first has a more specific
+    /// segment.
+    second_is_general: bool,
+    /// Both routes have a parameter of the same kind in the same position, but
+    /// with a different name.
+    differently_named: bool,
+    /// Both routes have a named parameter in the same position, but with a
+    /// different constraint.
+    differently_constrained: bool,
+}
+
+impl Overlap {
+    /// Combines the flags of `other` into `self`.
+    fn merge(&mut self, other: Overlap) {
+        self.first_is_general |= other.first_is_general;
+        self.second_is_general |= other.second_is_general;
+        self.differently_named |= other.differently_named;
+        self.differently_constrained |= other.differently_constrained;
+    }
+}
+
+/// Returns `true` if the static segment `value` could be matched by a named
+/// parameter with `constraint`.
+fn satisfies(constraint: Option<&Constraint>, value: &str) -> bool {
+    constraint.is_none_or(|constraint| constraint.is_satisfied_by(&decode_segment(value)))
+}
+
+/// Returns `false` if the static text at the start or end of two mixed
+/// segments rules out a value matching both.
+fn may_overlap(first: &[SegmentPart], second: &[SegmentPart]) -> bool {
+    let compatible = |first: Option<&SegmentPart>,
+                      second: Option<&SegmentPart>,
+                      extends: fn(&str, &str) -> bool| match (first, second) {
+        (Some(SegmentPart::Static(first)), Some(SegmentPart::Static(second))) => {
+            extends(first, second) || extends(second, first)
+        }
+        _ => true,
+    };
+
+    compatible(first.first(), second.first(), |value, prefix| {
+        value.starts_with(prefix)
+    }) && compatible(first.last(), second.last(), |value, suffix| {
+        value.ends_with(suffix)
+    })
+}
+
+/// Compares `existing` and `route`, returning how they conflict if there is a
+/// path that would be matched by both.
+///
+/// Routes with optional parameters are compared through each of their
+/// expansions. An expansion that does not overlap with any expansion of the
+/// other route means its route matches paths that the other does not.
+fn find_conflict(
+    existing: &ParameterizedRoute,
+    route: &ParameterizedRoute,
+) -> Option<RouteConflict> {
+    let existing_expansions = expanded_segments(existing);
+    let route_expansions = expanded_segments(route);
+
+    let mut overlap = None::<Overlap>;
+    let mut route_overlapped = vec![false; route_expansions.len()];
+    let mut existing_is_wider = false;
+    for existing_segments in &existing_expansions {
+        let mut existing_overlapped = false;
+
+        for (route_segments, route_overlapped) in route_expansions.iter().zip(&mut route_overlapped)
+        {
+            if let Some(pair_overlap) = find_overlap(existing_segments, route_segments) {
+                existing_overlapped = true;
+                *route_overlapped = true;
+                overlap.get_or_insert_default().merge(pair_overlap);
+            }
+        }
+
+        existing_is_wider |= !existing_overlapped;
+    }
+
+    let mut overlap = overlap?;
+    overlap.first_is_general |= existing_is_wider;
+    overlap.second_is_general |= route_overlapped.contains(&false);
+
+    Some(
+        if overlap.differently_named
+            || overlap.differently_constrained
+            || (overlap.first_is_general && overlap.second_is_general)
+        {
+            RouteConflict::Ambiguous(existing.clone(), route.clone())
+        } else if overlap.first_is_general {
+            RouteConflict::Shadowed {
+                route: route.clone(),
+                by: existing.clone(),
+            }
+        } else if overlap.second_is_general {
+            RouteConflict::Shadowed {
+                route: existing.clone(),
+                by: route.clone(),
+            }
+        } else {
+            RouteConflict::Duplicate(route.clone())
+        },
+    )
+}
+
+/// The segments of each of the route's expansions (see
+/// [`ParameterizedRoute::expand_optional`]).
+fn expanded_segments(route: &ParameterizedRoute) -> Vec<Vec<ParameterizedSegment>> {
+    route
+        .expand_optional()
+        .iter()
+        .map(ParameterizedRoute::to_segments)
+        .collect()
+}
+
+/// Walks `first` and `second` segment by segment, returning [`None`] if no
+/// path could be matched by both.
+///
+/// A catch-all parameter matches one or more segments, so it is compared
+/// against all of the remaining segments of the other route.
+fn find_overlap(
+    first: &[ParameterizedSegment],
+    second: &[ParameterizedSegment],
+) -> Option<Overlap> {
+    use ParameterizedSegment::{CatchallParam, Mixed, NamedParam, Static};
+
+    let mut overlap = Overlap::default();
+
+    for position in 0.. {
+        match (first.get(position), second.get(position)) {
+            (None, None) => return Some(overlap),
+            (None, Some(_)) | (Some(_), None) => return None,
+            (Some(CatchallParam(first_param)), Some(CatchallParam(second_param))) => {
+                overlap.differently_named |= first_param != second_param;
+                return Some(overlap);
+            }
+            (Some(CatchallParam(_)), Some(_)) => {
+                overlap.first_is_general = true;
+                return Some(overlap);
+            }
+            (Some(_), Some(CatchallParam(_))) => {
+                overlap.second_is_general = true;
+                return Some(overlap);
+            }
+            (
+                Some(NamedParam {
+                    name: first_param,
+                    constraint: first_constraint,
+                    ..
+                }),
+                Some(NamedParam {
+                    name: second_param,
+                    constraint: second_constraint,
+                    ..
+                }),
+            ) => {
+                overlap.differently_named |= first_param != second_param;
+
+                match (first_constraint, second_constraint) {
+                    (None, Some(_)) => overlap.first_is_general = true,
+                    (Some(_), None) => overlap.second_is_general = true,
+                    // Whether two different constraints overlap cannot be
+                    // known in general, so it is assumed that they do.
+                    (Some(first_constraint), Some(second_constraint))
+                        if first_constraint != second_constraint =>
+                    {
+                        overlap.differently_constrained = true;
+                    }
+                    _ => {}
+                }
+            }
+            (Some(NamedParam { constraint, .. }), Some(Static(value))) => {
+                if !satisfies(constraint.as_ref(), value) {
+                    return None;
+                }
+                overlap.first_is_general = true;
+            }
+            (Some(Static(value)), Some(NamedParam { constraint, .. })) => {
+                if !satisfies(constraint.as_ref(), value) {
+                    return None;
+                }
+                overlap.second_is_general = true;
+            }
+            (Some(Mixed(first_parts)), Some(Mixed(second_parts))) => {
+                if unnamed_parts(first_parts) == unnamed_parts(second_parts) {
+                    overlap.differently_named |= first_parts != second_parts;
+                } else if may_overlap(first_parts, second_parts) {
+                    // Whether two different mixed segments overlap cannot be
+                    // known in general, so it is assumed that they do and
+                    // that neither is more specific.
+                    overlap.first_is_general = true;
+                    overlap.second_is_general = true;
+                } else {
+                    return None;
+                }
+            }
+            (Some(Mixed(parts)), Some(Static(value))) => {
+                match_parts(parts, value)?;
+                overlap.first_is_general = true;
+            }
+            (Some(Static(value)), Some(Mixed(parts))) => {
+                match_parts(parts, value)?;
+                overlap.second_is_general = true;
+            }
+            // A constrained named parameter may or may not match the values of
+            // a mixed segment, so neither is more specific.
+            (Some(NamedParam { constraint, .. }), Some(Mixed(_))) => {
+                overlap.first_is_general = true;
+                overlap.second_is_general |= constraint.is_some();
+            }
+            (Some(Mixed(_)), Some(NamedParam { constraint, .. })) => {
+                overlap.second_is_general = true;
+                overlap.first_is_general |= constraint.is_some();
+            }
+            (Some(Static(first_value)), Some(Static(second_value))) => {
+                if first_value != second_value {
+                    return None;
+                }
+            }
+        }
+    }
+
+    unreachable!("the loop only ends by returning")
+}
+
+#[cfg(test)]
+mod route_set_tests {
+    use super::*;
+
+    mod insert {
+        use super::*;
+
+        fn insert_both(existing: &str, route: &str) -> Result<(), RouteConflict> {
+            let mut route_set = RouteSet::new();
+            route_set
+                .insert(ParameterizedRoute::new(existing))
+                .expect("first route should not conflict");
+
+            route_set.insert(ParameterizedRoute::new(route))
+        }
+
+        #[test]
+        fn should_accept_disjoint_routes() {
+            for (existing, route) in [
+                ("/users/{id}", "/posts/{id}"),
+                ("/users/{id}", "/users/{id}/posts"),
+                ("/users/{id}/a", "/users/{name}/b"),
+                ("/files", "/files/{*path}"),
+                ("/", "/{id}"),
+                ("/users/{id:int}", "/users/me"),
+            ] {
+                // Act
+                let res = insert_both(existing, route);
+
+                // Assert
+                assert_eq!(res, Ok(()), "{existing} and {route} should not conflict");
+            }
+        }
+
+        #[test]
+        fn should_report_duplicate() {
+            // Act
+            let res = insert_both("/users/{id}{?page}", "users//{id}/");
+
+            // Assert
+            assert_eq!(
+                res,
+                Err(RouteConflict::Duplicate(ParameterizedRoute::new(
+                    "/users/{id}"
+                )))
+            );
+        }
+
+        #[test]
+        fn should_report_differently_named_params_as_ambiguous() {
+            // Act
+            let named = insert_both("/users/{id}", "/users/{name}");
+            let catchall = insert_both("/files/{*path}", "/files/{*rest}");
+
+            // Assert
+            assert!(matches!(named, Err(RouteConflict::Ambiguous(_, _))));
+            assert!(matches!(catchall, Err(RouteConflict::Ambiguous(_, _))));
+        }
+
+        #[test]
+        fn should_report_crossed_params_as_ambiguous() {
+            // Act
+            let res = insert_both("/{a}/x", "/x/{b}");
+
+            // Assert
+            assert!(matches!(res, Err(RouteConflict::Ambiguous(_, _))));
+        }
+
+        #[test]
+        fn should_report_static_segment_shadowed_by_param() {
+            // Act
+            let res = insert_both("/users/new", "/users/{id}");
+
+            // Assert
+            assert_eq!(
+                res,
+                Err(RouteConflict::Shadowed {
+                    route: ParameterizedRoute::new("/users/new"),
+                    by: ParameterizedRoute::new("/users/{id}"),
+                })
+            );
+        }
+
+        #[test]
+        fn should_report_constrained_param_shadowed_by_unconstrained_param() {
+            // Act
+            let res = insert_both("/users/{id}", "/users/{id:int}");
+
+            // Assert
+            assert_eq!(
+                res,
+                Err(RouteConflict::Shadowed {
+                    route: ParameterizedRoute::new("/users/{id:int}"),
+                    by: ParameterizedRoute::new("/users/{id}"),
+                })
+            );
+        }
+
+        #[test]
+        fn should_report_route_shadowed_by_catchall() {
+            // Act
+            let res = insert_both("/files/{*path}", "/files/{id}/new");
+
+            // Assert
+            assert_eq!(
+                res,
+                Err(RouteConflict::Shadowed {
+                    route: ParameterizedRoute::new("/files/{id}/new"),
+                    by: ParameterizedRoute::new("/files/{*path}"),
+                })
+            );
+        }
+
+        #[test]
+        fn should_report_route_shadowed_by_optional_param() {
+            // Act
+            let shorter = insert_both("/reports/{year?}", "/reports");
+            let longer = insert_both("/reports/{year}", "/reports/{year?}");
+
+            // Assert
+            assert_eq!(
+                shorter,
+                Err(RouteConflict::Shadowed {
+                    route: ParameterizedRoute::new("/reports"),
+                    by: ParameterizedRoute::new("/reports/{year?}"),
+                })
+            );
+            assert_eq!(
+                longer,
+                Err(RouteConflict::Shadowed {
+                    route: ParameterizedRoute::new("/reports/{year}"),
+                    by: ParameterizedRoute::new("/reports/{year?}"),
+                })
+            );
+        }
+
+        #[test]
+        fn should_accept_route_beyond_optional_param() {
+            // Act
+            let res = insert_both("/reports/{year?}", "/reports/{year}/summary");
+
+            // Assert
+            assert_eq!(res, Ok(()));
+        }
+
+        #[test]
+        fn should_compare_mixed_segments() {
+            // Act
+            let disjoint_static = insert_both("/files/{name}.json", "/files/readme.md");
+            let disjoint_mixed = insert_both("/files/{name}.json", "/files/{name}.xml");
+            let shadowed = insert_both("/files/{name}.json", "/files/readme.json");
+            let renamed = insert_both("/files/{name}.json", "/files/{id}.json");
+            let general = insert_both("/files/{id}", "/files/{name}.json");
+
+            // Assert
+            assert_eq!(disjoint_static, Ok(()));
+            assert_eq!(disjoint_mixed, Ok(()));
+            assert!(matches!(shadowed, Err(RouteConflict::Shadowed { .. })));
+            assert!(matches!(renamed, Err(RouteConflict::Ambiguous(_, _))));
+            assert!(matches!(general, Err(RouteConflict::Shadowed { .. })));
+        }
+
+        #[test]
+        fn should_not_add_conflicting_route() {
+            // Arrange
+            let mut route_set = RouteSet::new();
+            route_set
+                .insert(ParameterizedRoute::new("/users/{id}"))
+                .unwrap();
+
+            // Act
+            let _ = route_set.insert(ParameterizedRoute::new("/users/{name}"));
+
+            // Assert
+            assert_eq!(route_set.len(), 1);
+            assert!(!route_set.contains(&ParameterizedRoute::new("/users/{name}")));
+        }
+    }
+}
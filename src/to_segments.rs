@@ -3,34 +3,49 @@
 use std::{cell::LazyCell, sync::LazyLock};
 
 use crate::{
-    ParameterizedRoute, WebRoute, parameterized_route::segment::ParameterizedSegment,
+    ParameterizedRoute, WebRoute,
+    parameterized_route::segment::ParameterizedSegment,
+    utils::{split_query, split_query_template},
     web_route::segment::WebSegment,
 };
 
 pub trait ToFixedSegments {
     /// Defines how to convert something into a [`Vec`] of [`FixedSegment`]s.
     fn to_segments(&self) -> Vec<WebSegment>;
+
+    /// Defines the query component (without the leading `?`) that is carried
+    /// along with the segments, if any.
+    fn to_query(&self) -> Option<String> {
+        None
+    }
 }
 
 impl ToFixedSegments for &str {
     fn to_segments(&self) -> Vec<WebSegment> {
-        self.trim_start_matches("/")
+        let (path, _) = split_query(self);
+
+        path.trim_start_matches("/")
             .trim_end_matches("/")
             .split("/")
             .map(TryInto::try_into)
             .filter_map(|res| res.ok())
             .collect()
     }
+
+    fn to_query(&self) -> Option<String> {
+        let (_, query) = split_query(self);
+
+        query.map(ToOwned::to_owned)
+    }
 }
 
 impl ToFixedSegments for String {
     fn to_segments(&self) -> Vec<WebSegment> {
-        self.trim_start_matches("/")
-            .trim_end_matches("/")
-            .split("/")
-            .map(TryInto::try_into)
-            .filter_map(|res| res.ok())
-            .collect()
+        ToFixedSegments::to_segments(&self.as_str())
+    }
+
+    fn to_query(&self) -> Option<String> {
+        ToFixedSegments::to_query(&self.as_str())
     }
 }
 
@@ -38,24 +53,88 @@ impl ToFixedSegments for WebRoute {
     fn to_segments(&self) -> Vec<WebSegment> {
         WebRoute::to_segments(self)
     }
+
+    fn to_query(&self) -> Option<String> {
+        self.query().map(ToOwned::to_owned)
+    }
 }
 
 impl ToFixedSegments for &WebRoute {
     fn to_segments(&self) -> Vec<WebSegment> {
         WebRoute::to_segments(self)
     }
+
+    fn to_query(&self) -> Option<String> {
+        self.query().map(ToOwned::to_owned)
+    }
 }
 
 impl ToFixedSegments for LazyCell<WebRoute> {
     fn to_segments(&self) -> Vec<WebSegment> {
         WebRoute::to_segments(self)
     }
+
+    fn to_query(&self) -> Option<String> {
+        self.query().map(ToOwned::to_owned)
+    }
 }
 
 impl ToFixedSegments for LazyLock<WebRoute> {
     fn to_segments(&self) -> Vec<WebSegment> {
         WebRoute::to_segments(self)
     }
+
+    fn to_query(&self) -> Option<String> {
+        self.query().map(ToOwned::to_owned)
+    }
+}
+
+#[cfg(feature = "http")]
+impl ToFixedSegments for http::Uri {
+    fn to_segments(&self) -> Vec<WebSegment> {
+        ToFixedSegments::to_segments(&self.path())
+    }
+
+    fn to_query(&self) -> Option<String> {
+        self.query()
+            .filter(|query| !query.is_empty())
+            .map(ToOwned::to_owned)
+    }
+}
+
+#[cfg(feature = "http")]
+impl ToFixedSegments for &http::Uri {
+    fn to_segments(&self) -> Vec<WebSegment> {
+        ToFixedSegments::to_segments(*self)
+    }
+
+    fn to_query(&self) -> Option<String> {
+        ToFixedSegments::to_query(*self)
+    }
+}
+
+#[cfg(feature = "http")]
+impl ToFixedSegments for http::uri::PathAndQuery {
+    fn to_segments(&self) -> Vec<WebSegment> {
+        ToFixedSegments::to_segments(&self.path())
+    }
+
+    fn to_query(&self) -> Option<String> {
+        self.query()
+            .filter(|query| !query.is_empty())
+            .map(ToOwned::to_owned)
+    }
+}
+
+#[cfg(feature = "http")]
+impl ToFixedSegments for &http::uri::PathAndQuery {
+    fn to_segments(&self) -> Vec<WebSegment> {
+        ToFixedSegments::to_segments(*self)
+    }
+
+    fn to_query(&self) -> Option<String> {
+        ToFixedSegments::to_query(*self)
+    }
 }
 
 #[cfg(feature = "uuid")]
@@ -76,27 +155,40 @@ pub trait ToParameterizedSegments {
     /// Defines how to convert something into a [`Vec`] of
     /// [`ParameterizedSegment`]s.
     fn to_segments(&self) -> Vec<ParameterizedSegment>;
+
+    /// Defines the names of the query parameters that are carried along with
+    /// the segments, if any.
+    fn to_query_params(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 impl ToParameterizedSegments for &str {
     fn to_segments(&self) -> Vec<ParameterizedSegment> {
-        self.trim_start_matches("/")
+        let (path, _) = split_query_template(self);
+
+        path.trim_start_matches("/")
             .trim_end_matches("/")
             .split("/")
             .map(TryInto::try_into)
             .filter_map(|res| res.ok())
             .collect()
     }
+
+    fn to_query_params(&self) -> Vec<String> {
+        let (_, query_params) = split_query_template(self);
+
+        query_params.into_iter().map(ToOwned::to_owned).collect()
+    }
 }
 
 impl ToParameterizedSegments for String {
     fn to_segments(&self) -> Vec<ParameterizedSegment> {
-        self.trim_start_matches("/")
-            .trim_end_matches("/")
-            .split("/")
-            .map(TryInto::try_into)
-            .filter_map(|res| res.ok())
-            .collect()
+        ToParameterizedSegments::to_segments(&self.as_str())
+    }
+
+    fn to_query_params(&self) -> Vec<String> {
+        ToParameterizedSegments::to_query_params(&self.as_str())
     }
 }
 
@@ -104,24 +196,40 @@ impl ToParameterizedSegments for ParameterizedRoute {
     fn to_segments(&self) -> Vec<ParameterizedSegment> {
         ParameterizedRoute::to_segments(self)
     }
+
+    fn to_query_params(&self) -> Vec<String> {
+        self.query_params()
+    }
 }
 
 impl ToParameterizedSegments for &ParameterizedRoute {
     fn to_segments(&self) -> Vec<ParameterizedSegment> {
         ParameterizedRoute::to_segments(self)
     }
+
+    fn to_query_params(&self) -> Vec<String> {
+        self.query_params()
+    }
 }
 
 impl ToParameterizedSegments for LazyCell<ParameterizedRoute> {
     fn to_segments(&self) -> Vec<ParameterizedSegment> {
         ParameterizedRoute::to_segments(self)
     }
+
+    fn to_query_params(&self) -> Vec<String> {
+        self.query_params()
+    }
 }
 
 impl ToParameterizedSegments for LazyLock<ParameterizedRoute> {
     fn to_segments(&self) -> Vec<ParameterizedSegment> {
         ParameterizedRoute::to_segments(self)
     }
+
+    fn to_query_params(&self) -> Vec<String> {
+        self.query_params()
+    }
 }
 
 // Could not do a generic implementation of `impl<T: ToFixedSegments>
@@ -204,6 +312,29 @@ mod to_fixed_segment_tests {
         // Assert
         assert_eq!(segments.len(), 2);
     }
+
+    #[test]
+    fn str_should_split_off_query() {
+        // Arrange
+        let route = "/foo/bar?baz=a/b&qux";
+
+        // Act
+        let segments = ToFixedSegments::to_segments(&route);
+        let query = ToFixedSegments::to_query(&route);
+
+        // Assert
+        assert_eq!(segments.len(), 2);
+        assert_eq!(query.as_deref(), Some("baz=a/b&qux"));
+    }
+
+    #[test]
+    fn str_should_ignore_empty_query() {
+        // Act
+        let query = ToFixedSegments::to_query(&"/foo?");
+
+        // Assert
+        assert_eq!(query, None);
+    }
 }
 
 #[cfg(test)]
@@ -233,4 +364,18 @@ mod to_parameterized_segment_tests {
         // Assert
         assert_eq!(segments.len(), 2);
     }
+
+    #[test]
+    fn str_should_split_off_query_params() {
+        // Arrange
+        let route = "/foo/{bar}{?page, per_page}";
+
+        // Act
+        let segments = ToParameterizedSegments::to_segments(&route);
+        let query_params = ToParameterizedSegments::to_query_params(&route);
+
+        // Assert
+        assert_eq!(segments.len(), 2);
+        assert_eq!(query_params, vec!["page", "per_page"]);
+    }
 }
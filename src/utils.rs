@@ -1,26 +1,1116 @@
-use serde::Serialize;
-use serde_json::{Value, to_value};
-use std::collections::HashMap;
-
-pub(crate) fn struct_to_map<T: Serialize>(input: &T) -> Option<HashMap<String, String>> {
-    match to_value(input).ok()? {
-        Value::Object(map) => {
-            let string_map = map
-                .into_iter()
-                .filter_map(|(k, v)| {
-                    let value_str = match v {
-                        Value::String(s) => s,
-                        Value::Number(n) => n.to_string(),
-                        Value::Bool(b) => b.to_string(),
-                        Value::Null | Value::Array(_) | Value::Object(_) => return None,
-                    };
-                    Some((k, value_str))
-                })
+use percent_encoding::{
+    AsciiSet, CONTROLS, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode,
+};
+use serde::{
+    Serialize,
+    de::{self, DeserializeOwned, IntoDeserializer, value::MapDeserializer},
+    forward_to_deserialize_any,
+};
+use std::{collections::HashMap, fmt};
+
+use serde::ser::{self, Impossible};
+use serde_json::Value;
+
+use crate::error::{SyntaxError, WebRouteError};
+
+/// Everything except the RFC 3986 "unreserved" characters is percent-encoded
+/// when a value is placed into a segment.
+const SEGMENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The characters that are not allowed anywhere in the path or query of a URI
+/// and are percent-encoded when a route is converted into one.
+const URI_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+
+/// The characters that are percent-encoded when a value is placed into a
+/// fragment, as specified by the WHATWG URL standard (along with `#` and `%`).
+const FRAGMENT_ENCODE_SET: &AsciiSet = &URI_ENCODE_SET.add(b'%');
+
+/// RFC 3986 percent-encodes `value` so that it can be used as a single path
+/// segment.
+///
+/// The `.` and `..` dot-segments are fully encoded so that they cannot be
+/// interpreted as a path traversal.
+pub(crate) fn encode_segment(value: &str) -> String {
+    match value {
+        "." => "%2E".to_owned(),
+        ".." => "%2E%2E".to_owned(),
+        _ => utf8_percent_encode(value, SEGMENT_ENCODE_SET).to_string(),
+    }
+}
+
+/// Percent-encodes `value` so that it can be used as a fragment.
+pub(crate) fn encode_fragment(value: &str) -> String {
+    utf8_percent_encode(value, FRAGMENT_ENCODE_SET).to_string()
+}
+
+/// Percent-encodes any characters of `route` that are not allowed in the path
+/// or query of a URI. Existing percent-encoding is left as is.
+#[cfg(feature = "http")]
+pub(crate) fn encode_uri(route: &str) -> String {
+    utf8_percent_encode(route, URI_ENCODE_SET).to_string()
+}
+
+/// Splits `route` into its path and (non-empty) query components.
+pub(crate) fn split_query(route: &str) -> (&str, Option<&str>) {
+    match route.split_once('?') {
+        Some((path, query)) => (path, (!query.is_empty()).then_some(query)),
+        None => (route, None),
+    }
+}
+
+/// Splits the path of a route into its non-empty segments, along with their
+/// position.
+pub(crate) fn split_segments(path: &str) -> impl Iterator<Item = (usize, &str)> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .enumerate()
+}
+
+/// Splits a [`ParameterizedRoute`](crate::ParameterizedRoute) template into its
+/// path and the names of its query parameters (e.g. `/items{?page,per_page}`).
+pub(crate) fn split_query_template(route: &str) -> (&str, Vec<&str>) {
+    match route.split_once("{?") {
+        Some((path, query)) => {
+            let query = query.split_once('}').map_or(query, |(query, _)| query);
+            let params = query
+                .split(',')
+                .map(str::trim)
+                .filter(|param| !param.is_empty())
                 .collect();
-            Some(string_map)
+
+            (path, params)
+        }
+        None => (route, Vec::new()),
+    }
+}
+
+/// Ensures that `param` is a non-empty parameter name made up of ASCII
+/// alphanumerics and `_`, optionally with `.` separating the components of a
+/// nested name (e.g. `user.id`).
+pub(crate) fn validate_param_name(param: &str) -> Result<(), SyntaxError> {
+    if param.is_empty() {
+        return Err(SyntaxError::EmptyParamName);
+    }
+
+    if param.split('.').any(str::is_empty) {
+        return Err(SyntaxError::InvalidParamName('.'));
+    }
+
+    match param
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.')))
+    {
+        Some(c) => Err(SyntaxError::InvalidParamName(c)),
+        None => Ok(()),
+    }
+}
+
+/// Ensures that `value` does not contain any whitespace, control or `invalid`
+/// characters, and that any percent-encoding is well formed.
+pub(crate) fn validate_chars(value: &str, invalid: &[char]) -> Result<(), SyntaxError> {
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => {
+                let is_valid_escape = chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+                    && chars.next().is_some_and(|c| c.is_ascii_hexdigit());
+
+                if !is_valid_escape {
+                    return Err(SyntaxError::InvalidPercentEncoding);
+                }
+            }
+            c if invalid.contains(&c) || c.is_whitespace() || c.is_control() => {
+                return Err(SyntaxError::InvalidCharacter(c));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the form-encoded pairs of `query` whose key is one of the declared
+/// `query_params`. An unparsable `query` is treated as having no pairs.
+pub(crate) fn declared_query_pairs(
+    query: Option<&str>,
+    query_params: &[String],
+) -> Vec<(String, String)> {
+    query
+        .and_then(|query| serde_urlencoded::from_str::<Vec<(String, String)>>(query).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(key, _)| query_params.contains(key))
+        .collect()
+}
+
+/// Combines two query strings, with the pairs of `first` preceding those of
+/// `second`.
+pub(crate) fn join_queries(first: Option<String>, second: Option<String>) -> Option<String> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(format!("{first}&{second}")),
+        (first, second) => first.or(second),
+    }
+}
+
+/// Decodes a percent-encoded path segment. Invalid UTF-8 sequences are
+/// replaced with `U+FFFD`.
+pub(crate) fn decode_segment(segment: &str) -> String {
+    percent_decode_str(segment).decode_utf8_lossy().into_owned()
+}
+
+/// The serialized value of a parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ParamValue {
+    /// A string, number, bool or unit enum variant.
+    Scalar(String),
+    /// A sequence of scalars, e.g. the segments of a catch-all parameter.
+    Sequence(Vec<String>),
+}
+
+/// Looks up the value of the field `name` in the serialized `values`, without
+/// serializing any of the other fields' values.
+///
+/// `values` needs to serialize into key-value pairs, either as a struct, a map
+/// or a sequence of `(key, value)` tuples. A dotted `name` (e.g. `user.id`)
+/// looks up the field `id` of the struct or map in the field `user`.
+///
+/// Values are represented in the same way as `serde_json` would, except that
+/// strings are not quoted. Sequences keep those of their elements that have a
+/// value. Fields that are `None`, or whose value is not a string, number,
+/// bool, unit enum variant or sequence, have no value.
+///
+/// # Errors
+///
+/// [`WebRouteError::InvalidValue`] if `values` does not serialize into
+/// key-value pairs.
+pub(crate) fn serialized_param<T: Serialize + ?Sized>(
+    values: &T,
+    name: &str,
+) -> Result<Option<ParamValue>, WebRouteError> {
+    values
+        .serialize(ParamsSerializer { name })
+        .map_err(|_| WebRouteError::InvalidValue)
+}
+
+/// Lists the keys of the serialized `values`, which need to serialize into
+/// key-value pairs as with [`serialized_param`].
+///
+/// Unlike [`serialized_param`], all of the values are serialized (via
+/// `serde_json`), so this is only used when the keys are checked against the
+/// parameters of a route. The keys of a struct or map are sorted, whereas
+/// those of a sequence of `(key, value)` tuples keep their order.
+///
+/// # Errors
+///
+/// [`WebRouteError::InvalidValue`] if `values` does not serialize into
+/// key-value pairs.
+pub(crate) fn serialized_param_names<T: Serialize + ?Sized>(
+    values: &T,
+) -> Result<Vec<String>, WebRouteError> {
+    let pair_key = |pair: Value| match pair {
+        Value::Array(pair) => match pair.as_slice() {
+            [Value::String(key), _] => Ok(key.to_owned()),
+            [key @ (Value::Bool(_) | Value::Number(_)), _] => Ok(key.to_string()),
+            _ => Err(WebRouteError::InvalidValue),
+        },
+        _ => Err(WebRouteError::InvalidValue),
+    };
+
+    match serde_json::to_value(values).map_err(|_| WebRouteError::InvalidValue)? {
+        Value::Object(map) => Ok(map.into_iter().map(|(key, _)| key).collect()),
+        Value::Array(pairs) => pairs.into_iter().map(pair_key).collect(),
+        _ => Err(WebRouteError::InvalidValue),
+    }
+}
+
+/// The inverse of [`serialized_param`]. Deserializes the key-value pairs of `map`
+/// into `T`.
+///
+/// Values are parsed from their string representation when `T` expects a
+/// number or bool (in the same way as a web framework's path extractor would).
+pub(crate) fn map_to_struct<T: DeserializeOwned>(
+    map: HashMap<String, String>,
+) -> Result<T, de::value::Error> {
+    let deserializer = MapDeserializer::new(
+        map.into_iter()
+            .map(|(key, value)| (key, ParamDeserializer(value))),
+    );
+
+    T::deserialize(deserializer)
+}
+
+/// Deserializes a single parameter value from its string representation.
+struct ParamDeserializer(String);
+
+impl<'de> IntoDeserializer<'de, de::value::Error> for ParamDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Implements a `deserialize_*` method that parses the string value into the
+/// requested primitive type.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.parse() {
+                    Ok(parsed) => visitor.$visit(parsed),
+                    Err(_) => Err(de::Error::invalid_value(
+                        de::Unexpected::Str(&self.0),
+                        &visitor,
+                    )),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ParamDeserializer {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+/// The error of a value that does not serialize into key-value pairs.
+#[derive(Debug)]
+struct NotParams;
+
+impl fmt::Display for NotParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "values do not serialize into key-value pairs")
+    }
+}
+
+impl std::error::Error for NotParams {}
+
+impl ser::Error for NotParams {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        NotParams
+    }
+}
+
+/// Implements `serialize_*` methods that reject values that are not made up
+/// of key-value pairs.
+macro_rules! reject_non_params {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Err(NotParams)
+            }
+        )*
+    };
+}
+
+/// Serializes the key-value pairs of a struct, map or sequence of `(key,
+/// value)` tuples, keeping only the value of the field `name`.
+struct ParamsSerializer<'a> {
+    name: &'a str,
+}
+
+impl<'a> ser::Serializer for ParamsSerializer<'a> {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+    type SerializeSeq = PairsSerializer<'a>;
+    type SerializeTuple = PairsSerializer<'a>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = FieldsSerializer<'a>;
+    type SerializeStruct = FieldsSerializer<'a>;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    reject_non_params! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(PairsSerializer {
+            name: self.name,
+            value: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(FieldsSerializer {
+            name: self.name,
+            key_match: KeyMatch::None,
+            value: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(NotParams)
+    }
+}
+
+/// Keeps the value of the field `name` of a struct or map. As with
+/// `serde_json`, the last value wins if a key is repeated.
+struct FieldsSerializer<'a> {
+    name: &'a str,
+    /// How the key that was last serialized matches `name`.
+    key_match: KeyMatch<'a>,
+    value: Option<ParamValue>,
+}
+
+/// How a key matches the (possibly dotted) name of a parameter.
+#[derive(Clone, Copy)]
+enum KeyMatch<'a> {
+    None,
+    /// The key is the name.
+    Exact,
+    /// The key is the first component of a dotted name, followed by the rest
+    /// of the name to look up in the key's value.
+    Nested(&'a str),
+}
+
+impl<'a> FieldsSerializer<'a> {
+    fn key_match(&self, key: &str) -> KeyMatch<'a> {
+        let name = self.name;
+        if name == key {
+            return KeyMatch::Exact;
+        }
+
+        match name
+            .strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix('.'))
+        {
+            Some(rest) => KeyMatch::Nested(rest),
+            None => KeyMatch::None,
+        }
+    }
+
+    fn serialize_matched_value<T: Serialize + ?Sized>(
+        &mut self,
+        key_match: KeyMatch<'a>,
+        value: &T,
+    ) -> Result<(), NotParams> {
+        match key_match {
+            KeyMatch::None => {}
+            KeyMatch::Exact => self.value = value.serialize(ValueSerializer)?,
+            // A nested value that is not made up of key-value pairs has no
+            // fields to look up, rather than being an error.
+            KeyMatch::Nested(rest) => {
+                self.value = value
+                    .serialize(ParamsSerializer { name: rest })
+                    .unwrap_or(None)
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for FieldsSerializer<'_> {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key_match = match key.serialize(ValueSerializer)? {
+            Some(ParamValue::Scalar(key)) => self.key_match(&key),
+            _ => KeyMatch::None,
+        };
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.serialize_matched_value(self.key_match, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.value)
+    }
+}
+
+impl ser::SerializeStruct for FieldsSerializer<'_> {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.serialize_matched_value(self.key_match(key), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.value)
+    }
+}
+
+/// Keeps the value of the `(key, value)` tuple whose key is `name`.
+struct PairsSerializer<'a> {
+    name: &'a str,
+    value: Option<ParamValue>,
+}
+
+impl PairsSerializer<'_> {
+    fn serialize_pair<T: Serialize + ?Sized>(&mut self, pair: &T) -> Result<(), NotParams> {
+        let (key, value) = pair.serialize(PairSerializer)?;
+
+        if key == Some(ParamValue::Scalar(self.name.to_owned())) {
+            self.value = value;
         }
-        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Array(_) => {
-            None
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for PairsSerializer<'_> {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, pair: &T) -> Result<(), Self::Error> {
+        self.serialize_pair(pair)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.value)
+    }
+}
+
+impl ser::SerializeTuple for PairsSerializer<'_> {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, pair: &T) -> Result<(), Self::Error> {
+        self.serialize_pair(pair)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.value)
+    }
+}
+
+/// Serializes a `(key, value)` tuple into its key and value.
+struct PairSerializer;
+
+impl ser::Serializer for PairSerializer {
+    type Ok = (Option<ParamValue>, Option<ParamValue>);
+    type Error = NotParams;
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = PairElementsSerializer;
+    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    reject_non_params! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        match len {
+            2 => Ok(PairElementsSerializer {
+                key: None,
+                value: None,
+                len: 0,
+            }),
+            _ => Err(NotParams),
+        }
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(NotParams)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(NotParams)
+    }
+}
+
+/// Keeps the key and value of a `(key, value)` tuple.
+struct PairElementsSerializer {
+    key: Option<ParamValue>,
+    value: Option<ParamValue>,
+    /// The number of elements that have been serialized.
+    len: usize,
+}
+
+impl ser::SerializeTuple for PairElementsSerializer {
+    type Ok = (Option<ParamValue>, Option<ParamValue>);
+    type Error = NotParams;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let element = value.serialize(ValueSerializer)?;
+        match self.len {
+            0 => self.key = element,
+            _ => self.value = element,
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok((self.key, self.value))
+    }
+}
+
+/// Implements `serialize_*` methods that represent a value by its string
+/// representation.
+macro_rules! serialize_to_string {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(Some(ParamValue::Scalar(value.to_string())))
+            }
+        )*
+    };
+}
+
+/// Implements `serialize_*` methods for values that have no representation as
+/// a single parameter value.
+macro_rules! serialize_to_none {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Ok(None)
+            }
+        )*
+    };
+}
+
+/// Serializes a single value into its string representation, or a sequence
+/// into those of its elements. Values that cannot be represented as strings
+/// (e.g. maps, `None`) have no value.
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+    type SerializeSeq = SequenceSerializer;
+    type SerializeTuple = SequenceSerializer;
+    type SerializeTupleStruct = IgnoredSerializer;
+    type SerializeTupleVariant = IgnoredSerializer;
+    type SerializeMap = IgnoredSerializer;
+    type SerializeStruct = IgnoredSerializer;
+    type SerializeStructVariant = IgnoredSerializer;
+
+    serialize_to_string! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    serialize_to_none! {
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+    }
+
+    /// Represented as `serde_json` would, e.g. `1.0` rather than `1`. Non-finite
+    /// values have no representation.
+    fn serialize_f32(self, value: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_f64(f64::from(value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(
+            serde_json::Number::from_f64(value)
+                .map(|number| ParamValue::Scalar(number.to_string())),
+        )
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(ParamValue::Scalar(variant.to_owned())))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SequenceSerializer(Vec::with_capacity(
+            len.unwrap_or_default(),
+        )))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(IgnoredSerializer)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(IgnoredSerializer)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(IgnoredSerializer)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(IgnoredSerializer)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(IgnoredSerializer)
+    }
+}
+
+/// Keeps the string representations of the elements of a sequence. Elements
+/// that have no representation as a string (e.g. nested sequences) are
+/// skipped.
+struct SequenceSerializer(Vec<String>);
+
+impl SequenceSerializer {
+    fn serialize_item<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NotParams> {
+        if let Some(ParamValue::Scalar(value)) = value.serialize(ValueSerializer)? {
+            self.0.push(value);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SequenceSerializer {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(ParamValue::Sequence(self.0)))
+    }
+}
+
+impl ser::SerializeTuple for SequenceSerializer {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(ParamValue::Sequence(self.0)))
+    }
+}
+
+/// Skips over a compound value, which has no representation as a single
+/// parameter value.
+struct IgnoredSerializer;
+
+/// Implements a `Serialize*` trait for [`IgnoredSerializer`] that skips over
+/// each of the compound value's elements.
+macro_rules! ignore_elements {
+    ($($trait:ident::$method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            impl ser::$trait for IgnoredSerializer {
+                type Ok = Option<ParamValue>;
+                type Error = NotParams;
+
+                fn $method<T: Serialize + ?Sized>(
+                    &mut self,
+                    $(_: $arg,)*
+                    _value: &T,
+                ) -> Result<(), Self::Error> {
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    Ok(None)
+                }
+            }
+        )*
+    };
+}
+
+ignore_elements! {
+    SerializeTupleStruct::serialize_field(),
+    SerializeTupleVariant::serialize_field(),
+    SerializeStruct::serialize_field(&'static str),
+    SerializeStructVariant::serialize_field(&'static str),
+}
+
+impl ser::SerializeMap for IgnoredSerializer {
+    type Ok = Option<ParamValue>;
+    type Error = NotParams;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, _key: &T) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod utils_tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    mod encode_segment {
+        use super::*;
+
+        #[test]
+        fn should_encode_reserved_characters() {
+            // Act
+            let encoded = encode_segment("a/b c%?#");
+
+            // Assert
+            assert_eq!(encoded, "a%2Fb%20c%25%3F%23");
+        }
+
+        #[test]
+        fn should_not_encode_unreserved_characters() {
+            // Act
+            let encoded = encode_segment("AZaz09-._~");
+
+            // Assert
+            assert_eq!(encoded, "AZaz09-._~");
+        }
+
+        #[test]
+        fn should_encode_dot_segments() {
+            // Act
+            let single_dot = encode_segment(".");
+            let double_dot = encode_segment("..");
+
+            // Assert
+            assert_eq!(single_dot, "%2E");
+            assert_eq!(double_dot, "%2E%2E");
+        }
+
+        #[test]
+        fn should_round_trip_with_decode_segment() {
+            // Arrange
+            let value = "hello world/100%/ünïcödé";
+
+            // Act
+            let decoded = decode_segment(&encode_segment(value));
+
+            // Assert
+            assert_eq!(decoded, value);
+        }
+    }
+
+    mod map_to_struct {
+        use super::*;
+
+        #[test]
+        fn should_parse_non_string_values() {
+            // Arrange
+            #[derive(Debug, PartialEq, Deserialize)]
+            struct Params {
+                id: u32,
+                flag: bool,
+                name: String,
+                maybe: Option<i64>,
+            }
+
+            let map = HashMap::from([
+                ("id".to_owned(), "42".to_owned()),
+                ("flag".to_owned(), "true".to_owned()),
+                ("name".to_owned(), "foo".to_owned()),
+                ("maybe".to_owned(), "-7".to_owned()),
+            ]);
+
+            // Act
+            let params = map_to_struct::<Params>(map).unwrap();
+
+            // Assert
+            assert_eq!(
+                params,
+                Params {
+                    id: 42,
+                    flag: true,
+                    name: "foo".to_owned(),
+                    maybe: Some(-7),
+                }
+            );
+        }
+
+        #[test]
+        fn should_error_on_unparsable_value() {
+            // Arrange
+            #[derive(Debug, Deserialize)]
+            #[allow(dead_code)]
+            struct Params {
+                id: u32,
+            }
+
+            let map = HashMap::from([("id".to_owned(), "not_a_number".to_owned())]);
+
+            // Act
+            let res = map_to_struct::<Params>(map);
+
+            // Assert
+            assert!(res.is_err());
         }
     }
 }
@@ -1,2 +1,2 @@
 pub mod route;
-pub(crate) mod segment;
+pub mod segment;
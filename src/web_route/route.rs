@@ -1,13 +1,23 @@
-use std::{fmt, ops};
+use std::{borrow::Cow, fmt, ops};
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{to_segments::ToFixedSegments, web_route::segment::WebSegment};
+#[cfg(feature = "http")]
+use crate::utils::encode_uri;
+use crate::{
+    error::{TraversalError, WebRouteError},
+    to_segments::ToFixedSegments,
+    utils::{join_queries, split_query, split_segments, validate_chars},
+    web_route::segment::WebSegment,
+};
 
 /// Defines a route structure that can be safely joined, no matter the
 /// leading/trailing slash configuration or operating system.
+///
+/// A route can optionally carry a query component (e.g. `/search?q=x`), which
+/// is preserved when routes are joined.
 #[derive(Clone, PartialEq)]
-pub struct WebRoute(String);
+pub struct WebRoute(pub(crate) Cow<'static, str>);
 
 impl WebRoute {
     /// Creates a new [`WebRoute`].
@@ -18,35 +28,410 @@ impl WebRoute {
     /// use web_route::WebRoute;
     ///
     /// let route = WebRoute::new("/some/route");
+    /// let route_with_query = WebRoute::new("/search?q=x");
     /// ```
     pub fn new<R: ToFixedSegments>(route: R) -> Self {
         let segments = route.to_segments();
 
-        Self(evaluate_segments(segments))
+        Self(Cow::Owned(evaluate_segments(segments, route.to_query())))
+    }
+
+    /// Strictly parses a new [`WebRoute`].
+    ///
+    /// Unlike [`WebRoute::new`], which works around malformed input, any
+    /// malformed segment or query is rejected. Repeated, leading and trailing
+    /// slashes are still normalized.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::InvalidSegment`] if a segment contains whitespace,
+    ///   control characters, `{`, `}` or `#`, or is not correctly
+    ///   percent-encoded.
+    /// - [`WebRouteError::InvalidQueryComponent`] if the query contains
+    ///   whitespace, control characters or `#`, or is not correctly
+    ///   percent-encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{WebRoute, error::WebRouteError};
+    ///
+    /// let route = WebRoute::parse("/search/items?q=x").unwrap();
+    /// assert_eq!(route, WebRoute::new("/search/items?q=x"));
+    ///
+    /// let res = WebRoute::parse("/search/100%");
+    /// assert!(matches!(res, Err(WebRouteError::InvalidSegment { position: 1, .. })));
+    /// ```
+    pub fn parse(route: &str) -> Result<Self, WebRouteError> {
+        let (path, query) = split_query(route);
+
+        let segments = split_segments(path)
+            .map(|(position, segment)| {
+                WebSegment::parse(segment)
+                    .map_err(|reason| WebRouteError::invalid_segment(position, segment, reason))
+            })
+            .collect::<Result<_, _>>()?;
+
+        if let Some(query) = query {
+            validate_chars(query, &['#'])
+                .map_err(|reason| WebRouteError::invalid_query_component(query, reason))?;
+        }
+
+        Ok(Self(Cow::Owned(evaluate_segments(
+            segments,
+            query.map(ToOwned::to_owned),
+        ))))
     }
 
     /// Joins a route onto an existing [`WebRoute`] returning the joined
     /// route.
     ///
+    /// If both routes have a query, the query of `route` is appended to that
+    /// of the existing [`WebRoute`].
+    ///
     /// # Examples
     ///
     /// ```
     /// use web_route::WebRoute;
     ///
-    /// let route = WebRoute::new("/some/route/");
+    /// let route = WebRoute::new("/some/route/?page=2");
     /// let nested_route = WebRoute::new("/a/nested/route");
     /// let joined_route = route.join(&nested_route);
     ///
-    /// assert_eq!(&joined_route.to_string(), "/some/route/a/nested/route")
+    /// assert_eq!(&joined_route.to_string(), "/some/route/a/nested/route?page=2")
     /// ```
     pub fn join<R: ToFixedSegments>(&self, route: R) -> Self {
         let joined_segments = [self.to_segments(), route.to_segments()].concat();
+        let joined_query = join_queries(self.query().map(ToOwned::to_owned), route.to_query());
+
+        Self(Cow::Owned(evaluate_segments(joined_segments, joined_query)))
+    }
+
+    /// Strictly parses untrusted `input` (e.g. a path requested by a user)
+    /// into a [`WebRoute`], ensuring that it cannot traverse above the root.
+    ///
+    /// This is [`WebRoute::join_untrusted`] onto the root route, see it for
+    /// details.
+    ///
+    /// # Errors
+    ///
+    /// See [`WebRoute::join_untrusted`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{
+    ///     WebRoute,
+    ///     error::{TraversalError, WebRouteError},
+    /// };
+    ///
+    /// let route = WebRoute::from_untrusted("/files/./a/../b.txt").unwrap();
+    /// assert_eq!(route, WebRoute::new("/files/b.txt"));
+    ///
+    /// let res = WebRoute::from_untrusted("/files/../../etc/passwd");
+    /// assert!(matches!(
+    ///     res,
+    ///     Err(WebRouteError::PathTraversal {
+    ///         position: 2,
+    ///         reason: TraversalError::EscapesBase,
+    ///         ..
+    ///     })
+    /// ));
+    /// ```
+    pub fn from_untrusted(input: &str) -> Result<Self, WebRouteError> {
+        Self::new("/").join_untrusted(input)
+    }
+
+    /// Strictly parses untrusted `input` (e.g. a path requested by a user)
+    /// and joins it onto the [`WebRoute`], ensuring that the result cannot
+    /// traverse above it.
+    ///
+    /// The `.` and `..` dot-segments of `input` are resolved as described in
+    /// [RFC 3986 section 5.2.4](https://www.rfc-editor.org/rfc/rfc3986#section-5.2.4),
+    /// except that a `..` which would remove a segment of the [`WebRoute`]
+    /// itself is rejected rather than ignored. Segments that could still be
+    /// used to traverse the path once they are decoded are rejected too.
+    ///
+    /// If both routes have a query, the query of `input` is appended to that
+    /// of the existing [`WebRoute`].
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::PathTraversal`] if a `..` segment would escape above
+    ///   the [`WebRoute`], or if a segment is a percent-encoded dot-segment
+    ///   (e.g. `%2e%2e`), contains a `\`, a percent-encoded `/` or `\`, or a
+    ///   percent-encoded null byte.
+    /// - Any of the errors of [`WebRoute::parse`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{
+    ///     WebRoute,
+    ///     error::{TraversalError, WebRouteError},
+    /// };
+    ///
+    /// let downloads = WebRoute::new("/downloads");
+    ///
+    /// let route = downloads.join_untrusted("reports/../invoices/1.pdf").unwrap();
+    /// assert_eq!(route, WebRoute::new("/downloads/invoices/1.pdf"));
+    ///
+    /// let res = downloads.join_untrusted("%2e%2e/secrets");
+    /// assert!(matches!(
+    ///     res,
+    ///     Err(WebRouteError::PathTraversal {
+    ///         reason: TraversalError::EncodedDotSegment,
+    ///         ..
+    ///     })
+    /// ));
+    /// ```
+    pub fn join_untrusted(&self, input: &str) -> Result<Self, WebRouteError> {
+        let (path, query) = split_query(input);
+
+        let mut resolved_segments = Vec::new();
+        for (position, segment) in split_segments(path) {
+            match segment {
+                "." => {}
+                ".." => {
+                    resolved_segments.pop().ok_or_else(|| {
+                        WebRouteError::path_traversal(
+                            position,
+                            segment,
+                            TraversalError::EscapesBase,
+                        )
+                    })?;
+                }
+                _ => {
+                    let web_segment = WebSegment::parse(segment).map_err(|reason| {
+                        WebRouteError::invalid_segment(position, segment, reason)
+                    })?;
+                    web_segment.check_traversal().map_err(|reason| {
+                        WebRouteError::path_traversal(position, segment, reason)
+                    })?;
+
+                    resolved_segments.push(web_segment);
+                }
+            }
+        }
+
+        if let Some(query) = query {
+            validate_chars(query, &['#'])
+                .map_err(|reason| WebRouteError::invalid_query_component(query, reason))?;
+        }
 
-        Self(evaluate_segments(joined_segments))
+        let joined_segments = [self.to_segments(), resolved_segments].concat();
+        let joined_query = join_queries(
+            self.query().map(ToOwned::to_owned),
+            query.map(ToOwned::to_owned),
+        );
+
+        Ok(Self(Cow::Owned(evaluate_segments(
+            joined_segments,
+            joined_query,
+        ))))
+    }
+
+    /// Returns the path component of the route, without any query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/search?q=x");
+    ///
+    /// assert_eq!(route.path(), "/search");
+    /// ```
+    pub fn path(&self) -> &str {
+        split_query(&self.0).0
+    }
+
+    /// Returns the query component of the route (without the leading `?`), if
+    /// it has one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/search?q=x");
+    ///
+    /// assert_eq!(route.query(), Some("q=x"));
+    /// assert_eq!(WebRoute::new("/search").query(), None);
+    /// ```
+    pub fn query(&self) -> Option<&str> {
+        split_query(&self.0).1
+    }
+
+    /// Form-encodes `query` and appends it to the route's query.
+    ///
+    /// `query` needs to implement `serde::Serialize` and be of an "Object"
+    /// style (with key-value pairs). Entries with a value of `None` are
+    /// omitted.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::InvalidQuery`] if `query` could not be form-encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Pagination {
+    ///     page: u32,
+    ///     sort: Option<String>,
+    /// }
+    ///
+    /// let route = WebRoute::new("/items")
+    ///     .with_query(&Pagination {
+    ///         page: 2,
+    ///         sort: Some("desc".to_owned()),
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(&route.to_string(), "/items?page=2&sort=desc");
+    /// ```
+    pub fn with_query<T: Serialize>(&self, query: &T) -> Result<Self, WebRouteError> {
+        let encoded = serde_urlencoded::to_string(query)
+            .map_err(|err| WebRouteError::InvalidQuery(err.to_string()))?;
+        let joined_query = join_queries(
+            self.query().map(ToOwned::to_owned),
+            (!encoded.is_empty()).then_some(encoded),
+        );
+
+        Ok(Self(Cow::Owned(evaluate_segments(
+            self.to_segments(),
+            joined_query,
+        ))))
+    }
+
+    /// Creates a new [`WebRoute`] from raw (unencoded) segment values. Each
+    /// value is percent-encoded so that it is treated as exactly one segment,
+    /// even if it contains a `/`. Empty values are skipped.
+    ///
+    /// This is the inverse of [`WebRoute::decoded_segments`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::from_decoded_segments(["files", "a/b c.txt"]);
+    ///
+    /// assert_eq!(&route.to_string(), "/files/a%2Fb%20c.txt");
+    /// assert_eq!(route.decoded_segments(), vec!["files", "a/b c.txt"]);
+    /// ```
+    pub fn from_decoded_segments<I, S>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let segments = segments
+            .into_iter()
+            .filter_map(|segment| WebSegment::from_decoded(segment.as_ref()))
+            .collect();
+
+        Self(Cow::Owned(evaluate_segments(segments, None)))
+    }
+
+    /// Returns the percent-decoded value of each of the route's segments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/hello%20world/100%25");
+    ///
+    /// assert_eq!(route.decoded_segments(), vec!["hello world", "100%"]);
+    /// ```
+    pub fn decoded_segments(&self) -> Vec<String> {
+        self.to_segments()
+            .iter()
+            .map(WebSegment::to_decoded)
+            .collect()
+    }
+
+    /// Returns an iterator over the segments of the route's path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/users/123/files?page=2");
+    /// let segments = route.segments().map(|segment| segment.to_string()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(segments, vec!["users", "123", "files"]);
+    /// ```
+    pub fn segments(&self) -> impl Iterator<Item = WebSegment> {
+        self.to_segments().into_iter()
+    }
+
+    /// Returns the number of segments in the route's path (e.g. `0` for `/`).
+    pub fn segment_count(&self) -> usize {
+        self.to_segments().len()
+    }
+
+    /// Returns the first segment of the route's path, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/users/123/files?page=2");
+    ///
+    /// assert_eq!(route.first().unwrap().to_string(), "users");
+    /// assert!(WebRoute::new("/").first().is_none());
+    /// ```
+    pub fn first(&self) -> Option<WebSegment> {
+        self.to_segments().into_iter().next()
+    }
+
+    /// Returns the last segment of the route's path, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/users/123/files?page=2");
+    ///
+    /// assert_eq!(route.last().unwrap().to_string(), "files");
+    /// assert!(WebRoute::new("/").last().is_none());
+    /// ```
+    pub fn last(&self) -> Option<WebSegment> {
+        self.to_segments().pop()
+    }
+
+    /// Returns the route without its last segment, or [`None`] if the route's
+    /// path has no segments.
+    ///
+    /// The query of the route is not carried over to the parent, as it belongs
+    /// to the route itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/users/123/files?page=2");
+    ///
+    /// assert_eq!(route.parent(), Some(WebRoute::new("/users/123")));
+    /// assert_eq!(WebRoute::new("/").parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<Self> {
+        let mut segments = self.to_segments();
+        segments.pop()?;
+
+        Some(Self(Cow::Owned(evaluate_segments(segments, None))))
     }
 
     pub(crate) fn to_segments(&self) -> Vec<WebSegment> {
-        ToFixedSegments::to_segments(&self.0)
+        ToFixedSegments::to_segments(&self.path())
     }
 }
 
@@ -99,6 +484,75 @@ impl AsRef<str> for WebRoute {
     }
 }
 
+/// Percent-encodes any characters that are not allowed in a URI.
+#[cfg(feature = "http")]
+impl TryFrom<&WebRoute> for http::uri::PathAndQuery {
+    type Error = WebRouteError;
+
+    fn try_from(route: &WebRoute) -> Result<Self, Self::Error> {
+        http::uri::PathAndQuery::try_from(encode_uri(route))
+            .map_err(|err| WebRouteError::InvalidUri(err.to_string()))
+    }
+}
+
+#[cfg(feature = "http")]
+impl TryFrom<WebRoute> for http::uri::PathAndQuery {
+    type Error = WebRouteError;
+
+    fn try_from(route: WebRoute) -> Result<Self, Self::Error> {
+        http::uri::PathAndQuery::try_from(&route)
+    }
+}
+
+/// Creates a relative [`http::Uri`] (i.e. one without a scheme or authority).
+#[cfg(feature = "http")]
+impl TryFrom<&WebRoute> for http::Uri {
+    type Error = WebRouteError;
+
+    fn try_from(route: &WebRoute) -> Result<Self, Self::Error> {
+        http::uri::PathAndQuery::try_from(route).map(Into::into)
+    }
+}
+
+#[cfg(feature = "http")]
+impl TryFrom<WebRoute> for http::Uri {
+    type Error = WebRouteError;
+
+    fn try_from(route: WebRoute) -> Result<Self, Self::Error> {
+        http::Uri::try_from(&route)
+    }
+}
+
+/// Uses the path and query of the URI (e.g. of an incoming request), ignoring
+/// its scheme and authority.
+#[cfg(feature = "http")]
+impl From<&http::Uri> for WebRoute {
+    fn from(uri: &http::Uri) -> Self {
+        WebRoute::new(uri)
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<http::Uri> for WebRoute {
+    fn from(uri: http::Uri) -> Self {
+        WebRoute::new(&uri)
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<&http::uri::PathAndQuery> for WebRoute {
+    fn from(path_and_query: &http::uri::PathAndQuery) -> Self {
+        WebRoute::new(path_and_query)
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<http::uri::PathAndQuery> for WebRoute {
+    fn from(path_and_query: http::uri::PathAndQuery) -> Self {
+        WebRoute::new(&path_and_query)
+    }
+}
+
 #[cfg(feature = "fake")]
 impl fake::Dummy<fake::Faker> for WebRoute {
     fn dummy_with_rng<R: fake::Rng + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
@@ -109,23 +563,127 @@ impl fake::Dummy<fake::Faker> for WebRoute {
     }
 }
 
-/// Convert `segments` into their normalized [`String`] route representation.
-fn evaluate_segments(segments: Vec<WebSegment>) -> String {
+/// Convert `segments` and `query` into their normalized [`String`] route
+/// representation.
+fn evaluate_segments(segments: Vec<WebSegment>, query: Option<String>) -> String {
     let evaluated_segments = segments
         .iter()
         .map(|segment| segment.to_evaluated())
         .collect::<Vec<_>>();
 
-    format!("/{}", evaluated_segments.join("/"))
+    match query {
+        Some(query) => format!("/{}?{query}", evaluated_segments.join("/")),
+        None => format!("/{}", evaluated_segments.join("/")),
+    }
 }
 
 #[cfg(test)]
-mod join_tests {
-    use fake::{Fake, Faker};
+mod decoded_segments_tests {
+    use crate::WebRoute;
 
+    #[test]
+    fn should_round_trip_with_from_decoded_segments() {
+        // Arrange
+        let segments = vec!["a/b", "hello world", "100%", ".."];
+
+        // Act
+        let route = WebRoute::from_decoded_segments(&segments);
+
+        // Assert
+        assert_eq!(route.to_segments().len(), segments.len());
+        assert_eq!(route.decoded_segments(), segments);
+    }
+
+    #[test]
+    fn should_skip_empty_segments() {
+        // Act
+        let route = WebRoute::from_decoded_segments(["foo", "", "bar"]);
+
+        // Assert
+        assert_eq!(&route.to_string(), "/foo/bar");
+    }
+}
+
+#[cfg(test)]
+mod query_tests {
+    use crate::WebRoute;
+
+    #[test]
+    fn should_parse_query_separately_from_segments() {
+        // Act
+        let route = WebRoute::new("search//items/?q=a/b");
+
+        // Assert
+        assert_eq!(&route.to_string(), "/search/items?q=a/b");
+        assert_eq!(route.path(), "/search/items");
+        assert_eq!(route.query(), Some("q=a/b"));
+        assert_eq!(route.to_segments().len(), 2);
+    }
+
+    #[test]
+    fn should_preserve_query_through_join() {
+        // Arrange
+        let route = WebRoute::new("/foo?a=1");
+
+        // Act
+        let joined_route = route.join("/bar");
+
+        // Assert
+        assert_eq!(&joined_route.to_string(), "/foo/bar?a=1");
+    }
+
+    #[test]
+    fn should_combine_queries_when_joining() {
+        // Arrange
+        let route = WebRoute::new("/foo?a=1");
+
+        // Act
+        let joined_route = route.join("/bar?b=2");
+
+        // Assert
+        assert_eq!(&joined_route.to_string(), "/foo/bar?a=1&b=2");
+    }
+
+    #[test]
+    fn should_form_encode_query() {
+        // Arrange
+        #[derive(serde::Serialize)]
+        struct Query {
+            q: String,
+            page: Option<u32>,
+        }
+
+        let route = WebRoute::new("/search?sort=desc");
+
+        // Act
+        let route = route
+            .with_query(&Query {
+                q: "a b&c".to_owned(),
+                page: None,
+            })
+            .unwrap();
+
+        // Assert
+        assert_eq!(&route.to_string(), "/search?sort=desc&q=a+b%26c");
+    }
+
+    #[test]
+    fn should_error_on_non_object_query() {
+        // Act
+        let res = WebRoute::new("/search").with_query(&"not an object");
+
+        // Assert
+        assert!(res.is_err());
+    }
+}
+
+#[cfg(test)]
+mod join_tests {
     #[cfg(feature = "uuid")]
     #[test]
     fn uuids_are_joined_with_hyphenated_string_representation() {
+        use fake::{Fake, Faker};
+
         // Arrange
         let base_route = Faker.fake::<crate::WebRoute>();
         let hyphenated_uuid_str = "9a878802-7b0f-4531-bcbb-9a88d4324a5f";
@@ -151,3 +709,255 @@ mod join_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod parse_tests {
+    use crate::{WebRoute, error::SyntaxError, error::WebRouteError};
+
+    #[test]
+    fn should_normalize_valid_route() {
+        // Act
+        let route = WebRoute::parse("search//items/?q=a/b").unwrap();
+
+        // Assert
+        assert_eq!(&route.to_string(), "/search/items?q=a/b");
+    }
+
+    #[test]
+    fn should_report_offending_segment() {
+        // Act
+        let res = WebRoute::parse("/foo//bar baz/qux");
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(WebRouteError::InvalidSegment {
+                position: 1,
+                segment,
+                reason: SyntaxError::InvalidCharacter(' '),
+            }) if segment == "bar baz"
+        ));
+    }
+
+    #[test]
+    fn should_reject_fragment() {
+        // Act
+        let res = WebRoute::parse("/search?q=x#results");
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(WebRouteError::InvalidQueryComponent {
+                reason: SyntaxError::InvalidCharacter('#'),
+                ..
+            })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod segments_tests {
+    use crate::WebRoute;
+
+    #[test]
+    fn should_not_count_query_as_segment() {
+        // Arrange
+        let route = WebRoute::new("/search/items?q=a/b");
+
+        // Act
+        let len = route.segment_count();
+        let last = route.last().unwrap();
+
+        // Assert
+        assert_eq!(len, 2);
+        assert_eq!(last.as_str(), "items");
+    }
+
+    #[test]
+    fn should_have_root_as_parent_of_single_segment() {
+        // Act
+        let parent = WebRoute::new("/foo").parent().unwrap();
+
+        // Assert
+        assert_eq!(parent.segment_count(), 0);
+        assert_eq!(&parent.to_string(), "/");
+    }
+
+    #[test]
+    fn should_keep_str_len_through_deref() {
+        // Arrange
+        let route = WebRoute::new("/foo/bar");
+
+        // Act
+        let len = route.len();
+
+        // Assert
+        assert_eq!(len, 8);
+        assert!(!WebRoute::new("/").is_empty());
+    }
+}
+
+#[cfg(test)]
+mod untrusted_tests {
+    use crate::{
+        WebRoute,
+        error::{SyntaxError, TraversalError, WebRouteError},
+    };
+
+    fn traversal_reason(res: Result<WebRoute, WebRouteError>) -> Option<TraversalError> {
+        match res {
+            Err(WebRouteError::PathTraversal { reason, .. }) => Some(reason),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn should_resolve_dot_segments() {
+        // Act
+        let route = WebRoute::from_untrusted("a/./b/../c/./d/..//e/.?q=../x").unwrap();
+
+        // Assert
+        assert_eq!(&route.to_string(), "/a/c/e?q=../x");
+    }
+
+    #[test]
+    fn should_reject_escaping_above_base() {
+        // Arrange
+        let base = WebRoute::new("/downloads/public");
+
+        // Act
+        let inside = base.join_untrusted("a/../b");
+        let escaping = base.join_untrusted("a/../../private/file");
+
+        // Assert
+        assert_eq!(inside.unwrap(), WebRoute::new("/downloads/public/b"));
+        assert!(matches!(
+            escaping,
+            Err(WebRouteError::PathTraversal {
+                position: 2,
+                segment,
+                reason: TraversalError::EscapesBase,
+            }) if segment == ".."
+        ));
+    }
+
+    #[test]
+    fn should_reject_encoded_traversal() {
+        for (input, reason) in [
+            ("/%2e%2e/etc", TraversalError::EncodedDotSegment),
+            ("/.%2E/etc", TraversalError::EncodedDotSegment),
+            ("/%2E", TraversalError::EncodedDotSegment),
+            ("/..%2Fetc", TraversalError::EncodedSeparator),
+            ("/..%5cetc", TraversalError::EncodedSeparator),
+            ("/..\\etc", TraversalError::Backslash),
+            ("/file%00.txt", TraversalError::NullByte),
+        ] {
+            // Act
+            let res = WebRoute::from_untrusted(input);
+
+            // Assert
+            assert_eq!(
+                traversal_reason(res),
+                Some(reason),
+                "{input} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_malformed_segments() {
+        // Act
+        let res = WebRoute::from_untrusted("/files/100%");
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(WebRouteError::InvalidSegment {
+                reason: SyntaxError::InvalidPercentEncoding,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn should_keep_encoded_characters_that_cannot_traverse() {
+        // Act
+        let route = WebRoute::from_untrusted("/files/annual%20report..pdf").unwrap();
+
+        // Assert
+        assert_eq!(
+            route.decoded_segments(),
+            vec!["files", "annual report..pdf"]
+        );
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod http_tests {
+    use crate::WebRoute;
+
+    #[test]
+    fn should_percent_encode_characters_not_allowed_in_uri() {
+        // Arrange
+        let route = WebRoute::new("/files/a b/r\u{e9}sum\u{e9}?q=<x>");
+
+        // Act
+        let path_and_query = http::uri::PathAndQuery::try_from(&route).unwrap();
+
+        // Assert
+        assert_eq!(path_and_query.path(), "/files/a%20b/r%C3%A9sum%C3%A9");
+        assert_eq!(path_and_query.query(), Some("q=%3Cx%3E"));
+    }
+
+    #[test]
+    fn should_round_trip_through_uri() {
+        // Arrange
+        let route = WebRoute::new("/users/123?tab=posts");
+
+        // Act
+        let uri = http::Uri::try_from(&route).unwrap();
+
+        // Assert
+        assert_eq!(uri, "/users/123?tab=posts");
+        assert_eq!(WebRoute::from(&uri), route);
+    }
+
+    #[test]
+    fn should_ignore_scheme_and_authority_of_uri() {
+        // Arrange
+        let uri = http::Uri::from_static("https://example.com//users/?");
+
+        // Act
+        let route = WebRoute::from(uri);
+
+        // Assert
+        assert_eq!(route, WebRoute::new("/users"));
+    }
+
+    #[test]
+    fn should_join_path_and_query() {
+        // Arrange
+        let path_and_query = http::uri::PathAndQuery::from_static("/b/c?d=e");
+
+        // Act
+        let route = WebRoute::new("/a?x=y").join(&path_and_query);
+
+        // Assert
+        assert_eq!(&route.to_string(), "/a/b/c?x=y&d=e");
+    }
+
+    #[test]
+    fn should_error_if_route_is_too_long() {
+        // Arrange
+        let route = WebRoute::new("a".repeat(u16::MAX as usize));
+
+        // Act
+        let res = http::Uri::try_from(route);
+
+        // Assert
+        assert!(matches!(
+            res,
+            Err(crate::error::WebRouteError::InvalidUri(_))
+        ));
+    }
+}
@@ -1,22 +1,123 @@
+use std::fmt;
+
+use crate::{
+    error::{SyntaxError, TraversalError},
+    utils::{decode_segment, encode_segment, validate_chars},
+};
+
 /// Represents an individual segment of a route (i.e. the bit between the `/`).
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "fake", derive(fake::Dummy))]
 pub struct WebSegment(String);
 
 impl WebSegment {
+    /// Returns the (percent-encoded) value of the segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/hello%20world");
+    ///
+    /// assert_eq!(route.last().unwrap().as_str(), "hello%20world");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the percent-decoded value of the segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebRoute;
+    ///
+    /// let route = WebRoute::new("/hello%20world");
+    ///
+    /// assert_eq!(route.last().unwrap().decoded(), "hello world");
+    /// ```
+    pub fn decoded(&self) -> String {
+        self.to_decoded()
+    }
+
     /// Returns the value of the segment.
     pub(crate) fn to_evaluated(&self) -> String {
         self.0.clone()
     }
+
+    /// Returns the percent-decoded value of the segment.
+    pub(crate) fn to_decoded(&self) -> String {
+        decode_segment(&self.0)
+    }
+
+    /// Creates a [`WebSegment`] from a raw (unencoded) `value`,
+    /// percent-encoding it so that it is always a single segment.
+    ///
+    /// Returns [`None`] if `value` is empty.
+    pub(crate) fn from_decoded(value: &str) -> Option<Self> {
+        (!value.is_empty()).then(|| Self(encode_segment(value)))
+    }
+
+    /// Strictly parses a [`WebSegment`] from an already encoded `value`.
+    ///
+    /// # Errors
+    ///
+    /// The [`SyntaxError`] if `value` is empty, contains whitespace, control
+    /// characters, `{`, `}`, `?` or `#`, or is not correctly percent-encoded.
+    pub(crate) fn parse(value: &str) -> Result<Self, SyntaxError> {
+        if value.is_empty() {
+            return Err(SyntaxError::EmptySegment);
+        }
+
+        validate_chars(value, &['{', '}', '?', '#'])?;
+
+        Ok(Self(value.to_owned()))
+    }
+
+    /// Ensures that the segment cannot be used to traverse the path once it
+    /// is decoded. Literal `.` and `..` segments are expected to have been
+    /// resolved already.
+    ///
+    /// # Errors
+    ///
+    /// The [`TraversalError`] describing why the segment is unsafe.
+    pub(crate) fn check_traversal(&self) -> Result<(), TraversalError> {
+        let decoded = self.to_decoded();
+
+        if matches!(decoded.as_str(), "." | "..") {
+            Err(TraversalError::EncodedDotSegment)
+        } else if self.0.contains('\\') {
+            Err(TraversalError::Backslash)
+        } else if decoded.contains(['/', '\\']) {
+            Err(TraversalError::EncodedSeparator)
+        } else if decoded.contains('\0') {
+            Err(TraversalError::NullByte)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for WebSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
-/// It is often a path of insecure traversals if there are two consecutive slashes in a path. Making an empty [`WebSegment`] impossible to create removes the chance of consecutive slashes.
+/// It is often a path of insecure traversals if there are two consecutive
+/// slashes in a path. Making an empty [`WebSegment`] impossible to create
+/// removes the chance of consecutive slashes.
+///
+/// Dot-segments (`.` and `..`) are kept as is, use
+/// [`WebRoute::from_untrusted`](crate::WebRoute::from_untrusted) to resolve
+/// them when the input cannot be trusted.
 impl TryFrom<&str> for WebSegment {
-    type Error = ();
+    type Error = SyntaxError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         if value.is_empty() {
-            Err(())
+            Err(SyntaxError::EmptySegment)
         } else {
             Ok(Self(value.to_owned()))
         }
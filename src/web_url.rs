@@ -0,0 +1,357 @@
+//! An absolute URL, made up of an origin and a [`WebRoute`].
+
+use std::fmt;
+
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::{
+    ParameterizedRoute, RouteParams, WebRoute,
+    error::{UrlError, WebRouteError},
+    to_segments::ToFixedSegments,
+    utils::{encode_fragment, validate_chars},
+};
+
+/// An absolute URL (e.g. `https://example.com/app/users/123?tab=posts#bio`),
+/// made up of an origin, a [`WebRoute`] and an optional fragment.
+///
+/// Routes are joined onto its [`WebRoute`] with the same normalization as
+/// [`WebRoute::join`], so absolute links for emails, OAuth callbacks or
+/// `Location` headers do not need to be assembled with `format!`.
+///
+/// # Examples
+///
+/// ```
+/// use web_route::{ParameterizedRoute, WebUrl};
+///
+/// #[derive(serde::Serialize)]
+/// struct UserParams {
+///     user_id: u32,
+/// }
+///
+/// let base_url = WebUrl::parse("https://Example.com/app/").unwrap();
+/// let user_route = ParameterizedRoute::new("/users/{user_id}");
+///
+/// let user_url = base_url
+///     .populate(&user_route, &UserParams { user_id: 123 })
+///     .unwrap()
+///     .with_fragment("bio");
+///
+/// assert_eq!(&user_url.to_string(), "https://example.com/app/users/123#bio");
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct WebUrl {
+    /// The normalized scheme and authority (e.g. `https://example.com:8080`).
+    origin: String,
+    route: WebRoute,
+    /// The (percent-encoded) fragment, without its leading `#`.
+    fragment: Option<String>,
+}
+
+impl WebUrl {
+    /// Strictly parses an absolute URL.
+    ///
+    /// The scheme and host are lowercased, and the path and query are parsed
+    /// with [`WebRoute::parse`], so repeated, leading and trailing slashes are
+    /// normalized.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::InvalidUrl`] if the scheme, authority or fragment are
+    ///   malformed. User information (e.g. `user@`) is not supported in the
+    ///   authority.
+    /// - Any of the errors of [`WebRoute::parse`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::{WebRoute, WebUrl};
+    ///
+    /// let url = WebUrl::parse("HTTPS://example.com:8080//app/?page=2#top").unwrap();
+    ///
+    /// assert_eq!(url.origin(), "https://example.com:8080");
+    /// assert_eq!(url.route(), &WebRoute::new("/app?page=2"));
+    /// assert_eq!(url.fragment(), Some("top"));
+    /// assert!(WebUrl::parse("example.com/app").is_err());
+    /// ```
+    pub fn parse(url: &str) -> Result<Self, WebRouteError> {
+        let invalid_url = |reason| WebRouteError::InvalidUrl {
+            url: url.to_owned(),
+            reason,
+        };
+
+        let (scheme, remaining) = url
+            .split_once("://")
+            .ok_or_else(|| invalid_url(UrlError::MissingScheme))?;
+
+        let is_valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        if !is_valid_scheme {
+            return Err(invalid_url(UrlError::InvalidScheme));
+        }
+
+        let authority_len = remaining.find(['/', '?', '#']).unwrap_or(remaining.len());
+        let (authority, remaining) = remaining.split_at(authority_len);
+
+        if authority.is_empty() {
+            return Err(invalid_url(UrlError::EmptyHost));
+        }
+
+        if let Some(c) = authority.chars().find(|c| {
+            c.is_whitespace() || c.is_control() || matches!(c, '@' | '\\' | '{' | '}' | '%')
+        }) {
+            return Err(invalid_url(UrlError::InvalidCharacter(c)));
+        }
+
+        let (route, fragment) = match remaining.split_once('#') {
+            Some((route, fragment)) => (route, (!fragment.is_empty()).then_some(fragment)),
+            None => (remaining, None),
+        };
+
+        if let Some(fragment) = fragment {
+            validate_chars(fragment, &['#'])
+                .map_err(|reason| invalid_url(UrlError::InvalidFragment(reason)))?;
+        }
+
+        Ok(Self {
+            origin: format!(
+                "{}://{}",
+                scheme.to_ascii_lowercase(),
+                authority.to_ascii_lowercase()
+            ),
+            route: WebRoute::parse(route)?,
+            fragment: fragment.map(ToOwned::to_owned),
+        })
+    }
+
+    /// Returns the scheme and authority of the URL (e.g.
+    /// `https://example.com:8080`).
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    /// Returns the path and query of the URL.
+    pub fn route(&self) -> &WebRoute {
+        &self.route
+    }
+
+    /// Returns the path component of the URL, without any query.
+    pub fn path(&self) -> &str {
+        self.route.path()
+    }
+
+    /// Returns the query component of the URL, without the leading `?`.
+    pub fn query(&self) -> Option<&str> {
+        self.route.query()
+    }
+
+    /// Returns the (percent-encoded) fragment of the URL, without the leading
+    /// `#`.
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// Joins a route onto the URL's [`WebRoute`] (see [`WebRoute::join`]),
+    /// returning the joined URL.
+    ///
+    /// The fragment belongs to the original URL, so it is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebUrl;
+    ///
+    /// let url = WebUrl::parse("https://example.com/api/?key=abc#docs").unwrap();
+    ///
+    /// assert_eq!(
+    ///     &url.join("/v1/users/").to_string(),
+    ///     "https://example.com/api/v1/users?key=abc"
+    /// );
+    /// ```
+    pub fn join<R: ToFixedSegments>(&self, route: R) -> Self {
+        Self {
+            origin: self.origin.clone(),
+            route: self.route.join(route),
+            fragment: None,
+        }
+    }
+
+    /// Populates `route` with `values` (see
+    /// [`ParameterizedRoute::to_web_route`]) and joins it onto the URL.
+    ///
+    /// # Errors
+    ///
+    /// Any of the errors of [`ParameterizedRoute::to_web_route`].
+    pub fn populate<P: RouteParams>(
+        &self,
+        route: &ParameterizedRoute,
+        values: P,
+    ) -> Result<Self, WebRouteError> {
+        Ok(self.join(&route.to_web_route(values)?))
+    }
+
+    /// Form-encodes `query` and appends it to the URL's query (see
+    /// [`WebRoute::with_query`]).
+    ///
+    /// # Errors
+    ///
+    /// - [`WebRouteError::InvalidQuery`] if `query` could not be form-encoded.
+    pub fn with_query<T: Serialize>(&self, query: &T) -> Result<Self, WebRouteError> {
+        Ok(Self {
+            route: self.route.with_query(query)?,
+            ..self.clone()
+        })
+    }
+
+    /// Sets the fragment of the URL, percent-encoding any characters that are
+    /// not allowed in it. An empty `fragment` removes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use web_route::WebUrl;
+    ///
+    /// let url = WebUrl::parse("https://example.com/docs").unwrap();
+    ///
+    /// assert_eq!(
+    ///     &url.with_fragment("section 2").to_string(),
+    ///     "https://example.com/docs#section%202"
+    /// );
+    /// ```
+    pub fn with_fragment(&self, fragment: &str) -> Self {
+        Self {
+            fragment: (!fragment.is_empty()).then(|| encode_fragment(fragment)),
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for WebUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.origin, self.route)?;
+
+        match &self.fragment {
+            Some(fragment) => write!(f, "#{fragment}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for WebUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WebUrl").field(&self.to_string()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for WebUrl {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for WebUrl {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        WebUrl::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod web_url_tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_normalize_origin_and_route() {
+            // Act
+            let url = WebUrl::parse("HTTP://LocalHost:3000").unwrap();
+
+            // Assert
+            assert_eq!(&url.to_string(), "http://localhost:3000/");
+            assert_eq!(url.path(), "/");
+        }
+
+        #[test]
+        fn should_report_why_url_is_invalid() {
+            for (url, reason) in [
+                ("example.com/app", UrlError::MissingScheme),
+                ("1http://example.com", UrlError::InvalidScheme),
+                ("https:///app", UrlError::EmptyHost),
+                ("https://user@example.com", UrlError::InvalidCharacter('@')),
+                (
+                    "https://example.com/#a b",
+                    UrlError::InvalidFragment(crate::error::SyntaxError::InvalidCharacter(' ')),
+                ),
+            ] {
+                // Act
+                let res = WebUrl::parse(url);
+
+                // Assert
+                assert!(
+                    matches!(&res, Err(WebRouteError::InvalidUrl { reason: err, .. }) if *err == reason),
+                    "{url} should be rejected with {reason:?}, got {res:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn should_report_invalid_path_segment() {
+            // Act
+            let res = WebUrl::parse("https://example.com/100%");
+
+            // Assert
+            assert!(matches!(res, Err(WebRouteError::InvalidSegment { .. })));
+        }
+    }
+
+    mod join {
+        use super::*;
+
+        #[test]
+        fn should_normalize_slashes_between_base_and_route() {
+            // Arrange
+            let url = WebUrl::parse("https://example.com/app/").unwrap();
+
+            // Act
+            let joined = url.join("//callback/").join(WebRoute::new("oauth?state=x"));
+
+            // Assert
+            assert_eq!(
+                &joined.to_string(),
+                "https://example.com/app/callback/oauth?state=x"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialization {
+        use super::*;
+
+        #[test]
+        fn should_round_trip_through_string() {
+            // Arrange
+            let url = WebUrl::parse("https://example.com/a?b=c#d").unwrap();
+
+            // Act
+            let json = serde_json::to_string(&url).unwrap();
+            let deserialized = serde_json::from_str::<WebUrl>(&json).unwrap();
+
+            // Assert
+            assert_eq!(json, "\"https://example.com/a?b=c#d\"");
+            assert_eq!(deserialized, url);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Ensures that malformed templates and routes are rejected at compile time by
+//! the `macros` feature.
+
+#[test]
+fn malformed_routes_should_not_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,693 @@
+//! Ensures that routes built at compile time by the `macros` feature are
+//! identical to those parsed and populated at runtime.
+
+use web_route::{
+    ParameterizedRoute, RouteParams, WebRoute, WebRoutes, error::WebRouteError, fixed_route, route,
+};
+
+const PARAMETERIZED_ROUTE: ParameterizedRoute = route!("foo//{foo_id}/bar/{*rest}/");
+static WEB_ROUTE: WebRoute = fixed_route!("foo//bar%20baz/");
+
+#[test]
+fn parameterized_route_should_match_runtime_parsing() {
+    // Act
+    let runtime_route = ParameterizedRoute::new("foo//{foo_id}/bar/{*rest}/");
+
+    // Assert
+    assert_eq!(PARAMETERIZED_ROUTE, runtime_route);
+    assert_eq!(&*PARAMETERIZED_ROUTE, "/foo/{foo_id}/bar/{*rest}");
+}
+
+#[test]
+fn web_route_should_match_runtime_parsing() {
+    // Act
+    let runtime_route = WebRoute::new("foo//bar%20baz/");
+
+    // Assert
+    assert_eq!(WEB_ROUTE, runtime_route);
+    assert_eq!(&*WEB_ROUTE, "/foo/bar%20baz");
+}
+
+#[test]
+fn parameterized_route_with_query_params_should_match_runtime_parsing() {
+    // Arrange
+    const ROUTE_WITH_QUERY_PARAMS: ParameterizedRoute = route!("/items/{?page, per_page}");
+
+    // Act
+    let runtime_route = ParameterizedRoute::new("/items/{?page, per_page}");
+
+    // Assert
+    assert_eq!(ROUTE_WITH_QUERY_PARAMS, runtime_route);
+    assert_eq!(&*ROUTE_WITH_QUERY_PARAMS, "/items");
+}
+
+#[test]
+fn parameterized_route_with_constraints_should_match_runtime_parsing() {
+    // Arrange
+    const CONSTRAINED_ROUTE: ParameterizedRoute = route!("/users/{id:int}/posts/{post:slug}");
+
+    // Act
+    let runtime_route = ParameterizedRoute::new("/users/{id:int}/posts/{post:slug}");
+
+    // Assert
+    assert_eq!(CONSTRAINED_ROUTE, runtime_route);
+    assert_eq!(&*CONSTRAINED_ROUTE, "/users/{id}/posts/{post}");
+}
+
+#[test]
+fn web_route_with_query_should_match_runtime_parsing() {
+    // Arrange
+    const ROUTE_WITH_QUERY: WebRoute = fixed_route!("/search/?q=x&page=2");
+
+    // Act
+    let runtime_route = WebRoute::new("/search/?q=x&page=2");
+
+    // Assert
+    assert_eq!(ROUTE_WITH_QUERY, runtime_route);
+    assert_eq!(ROUTE_WITH_QUERY.query(), Some("q=x&page=2"));
+}
+
+/// Pairs each template parsed at compile time with the template itself, to be
+/// parsed at runtime.
+macro_rules! compiled_routes {
+    ($macro:ident: $($template:literal),* $(,)?) => {
+        [$(($macro!($template), $template)),*]
+    };
+}
+
+#[test]
+fn every_template_construct_should_match_runtime_parsing() {
+    // Arrange
+    let routes = compiled_routes![route:
+        "",
+        "/",
+        "foo",
+        "/foo/bar/",
+        "//foo//bar//",
+        "/foo%20bar/caf%C3%A9",
+        "/foo/{foo_id}",
+        "/users/{id:int}",
+        "/users/{id:uuid}",
+        "/posts/{post:slug}",
+        "/reports/{year?}",
+        "/reports/{year?:int}/{month?}",
+        "/files/{*path}",
+        "/v{version:int}/files/{name}.{ext}",
+        "/archive/{year:int}-{month:int}",
+        "/owners/{owner.id}/repos/{owner.repo.name}",
+        "/items{?page}",
+        "/items/{id}/{?page, per_page}",
+        "/{?q}",
+        "/reports/{year?}{?page,sort}",
+    ];
+
+    for (compiled, template) in routes {
+        // Act
+        let runtime_route = ParameterizedRoute::new(template);
+
+        // Assert
+        assert_eq!(compiled, runtime_route, "{template}");
+        assert_eq!(
+            compiled.to_string(),
+            runtime_route.to_string(),
+            "{template}"
+        );
+        assert_eq!(&*compiled, &*runtime_route, "{template}");
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn every_regex_constraint_should_match_runtime_parsing() {
+    // Arrange
+    let routes = compiled_routes![route:
+        "/codes/{code:[a-z]{3}}",
+        "/images/{name}.{ext:png|jpe?g}",
+        "/versions/{version:\\d+\\.\\d+}/{*rest}",
+    ];
+
+    for (compiled, template) in routes {
+        // Act
+        let runtime_route = ParameterizedRoute::new(template);
+
+        // Assert
+        assert_eq!(compiled, runtime_route, "{template}");
+        assert_eq!(
+            compiled.to_string(),
+            runtime_route.to_string(),
+            "{template}"
+        );
+        assert_eq!(&*compiled, &*runtime_route, "{template}");
+    }
+}
+
+#[test]
+fn every_fixed_route_construct_should_match_runtime_parsing() {
+    // Arrange
+    let routes = compiled_routes![fixed_route:
+        "",
+        "/",
+        "foo",
+        "/foo/bar/",
+        "//foo//bar//",
+        "/foo%20bar/caf%C3%A9",
+        "/search?q=x&page=2",
+        "/search/?q=a%20b",
+        "/search?",
+    ];
+
+    for (compiled, route) in routes {
+        // Act
+        let runtime_route = WebRoute::new(route);
+
+        // Assert
+        assert_eq!(compiled, runtime_route, "{route}");
+    }
+}
+
+#[test]
+fn compile_time_routes_should_be_joinable() {
+    // Act
+    let joined = PARAMETERIZED_ROUTE.join(&WEB_ROUTE);
+
+    // Assert
+    assert_eq!(&*joined, "/foo/{foo_id}/bar/{*rest}/foo/bar%20baz");
+}
+
+#[derive(Debug, Clone, RouteParams)]
+#[route("/foo/{foo_id}/bar/{type}/{*rest}")]
+struct FooBarParams {
+    foo_id: u32,
+    r#type: String,
+    rest: String,
+}
+
+#[test]
+fn derived_route_should_match_runtime_parsing() {
+    // Act
+    let runtime_route = ParameterizedRoute::new("/foo/{foo_id}/bar/{type}/{*rest}");
+
+    // Assert
+    assert_eq!(FooBarParams::route(), runtime_route);
+}
+
+#[test]
+fn derived_to_web_route_should_match_runtime_population() {
+    // Arrange
+    let params = FooBarParams {
+        foo_id: 42,
+        r#type: "a/b c".to_owned(),
+        rest: "/x/y z/".to_owned(),
+    };
+
+    // Act
+    let web_route = params.to_web_route().unwrap();
+
+    // Assert
+    assert_eq!(
+        web_route,
+        FooBarParams::route().to_web_route(&params).unwrap()
+    );
+    assert_eq!(&*web_route, "/foo/42/bar/a%2Fb%20c/x/y%20z");
+}
+
+#[derive(Debug, Clone, RouteParams)]
+#[route("/items/{id}{?page,sort}")]
+struct ItemsParams {
+    id: String,
+    page: u32,
+    sort: Option<String>,
+}
+
+#[test]
+fn derived_to_web_route_should_populate_query_params() {
+    // Arrange
+    let params = ItemsParams {
+        id: "abc".to_owned(),
+        page: 2,
+        sort: None,
+    };
+
+    // Act
+    let web_route = params.to_web_route().unwrap();
+
+    // Assert
+    assert_eq!(
+        web_route,
+        ItemsParams::route().to_web_route(&params).unwrap()
+    );
+    assert_eq!(&*web_route, "/items/abc?page=2");
+}
+
+#[derive(RouteParams)]
+#[route("/orgs/{org_id}/members/{member}{?tab}")]
+struct MemberParams {
+    org_id: u32,
+    member: String,
+    tab: Option<String>,
+}
+
+#[test]
+fn derived_route_params_should_populate_other_routes() {
+    // Arrange
+    let params = MemberParams {
+        org_id: 7,
+        member: "a b".to_owned(),
+        tab: None,
+    };
+    let api_route = ParameterizedRoute::new("/api/orgs/{org_id}/members/{member}{?tab}");
+
+    // Act
+    let web_route = params.to_web_route().unwrap();
+    let api_web_route = api_route.to_web_route_strict(params);
+
+    // Assert
+    assert_eq!(&*web_route, "/orgs/7/members/a%20b");
+    assert_eq!(&*api_web_route.unwrap(), "/api/orgs/7/members/a%20b");
+}
+
+#[test]
+fn derived_route_params_should_populate_other_routes_by_reference() {
+    // Arrange
+    let params = MemberParams {
+        org_id: 7,
+        member: "me".to_owned(),
+        tab: Some("posts".to_owned()),
+    };
+    let api_route = ParameterizedRoute::new("/api/orgs/{org_id}/members/{member}{?tab}");
+
+    // Act
+    let api_web_route = api_route.to_web_route_strict(&params);
+
+    // Assert
+    assert_eq!(&*api_web_route.unwrap(), "/api/orgs/7/members/me?tab=posts");
+    assert_eq!(
+        &*params.to_web_route().unwrap(),
+        "/orgs/7/members/me?tab=posts"
+    );
+}
+
+#[test]
+fn derived_route_params_should_list_param_names() {
+    // Arrange
+    let params = MemberParams {
+        org_id: 7,
+        member: "me".to_owned(),
+        tab: Some("posts".to_owned()),
+    };
+
+    // Act
+    let names = RouteParams::param_names(&params);
+    let res = ParameterizedRoute::new("/orgs/{org_id}").to_web_route_strict(params);
+
+    // Assert
+    assert_eq!(names.unwrap(), vec!["org_id", "member", "tab"]);
+    assert!(matches!(
+        res,
+        Err(web_route::error::WebRouteError::MismatchedParams { unused, .. })
+            if unused == vec!["member", "tab"]
+    ));
+}
+
+#[derive(RouteParams)]
+#[route("/reports/{year?:int}/{month?}")]
+struct ReportsParams {
+    year: Option<u32>,
+    month: Option<u32>,
+}
+
+#[test]
+fn derived_to_web_route_should_omit_optional_params() {
+    // Arrange
+    let none = ReportsParams {
+        year: None,
+        month: None,
+    };
+    let year = ReportsParams {
+        year: Some(2024),
+        month: None,
+    };
+
+    // Act
+    let none_route = none.to_web_route().unwrap();
+    let year_route = year.to_web_route().unwrap();
+
+    // Assert
+    assert_eq!(
+        ReportsParams::route(),
+        ParameterizedRoute::new("/reports/{year?:int}/{month?}")
+    );
+    assert_eq!(
+        none_route,
+        ReportsParams::route().to_web_route(&none).unwrap()
+    );
+    assert_eq!(
+        year_route,
+        ReportsParams::route().to_web_route(&year).unwrap()
+    );
+    assert_eq!(&*year_route, "/reports/2024");
+}
+
+#[derive(RouteParams)]
+#[route("/v{version:int}/files/{name}.{ext}")]
+struct FileParams {
+    version: u32,
+    name: String,
+    ext: String,
+}
+
+#[test]
+fn derived_to_web_route_should_populate_mixed_segments() {
+    // Arrange
+    let params = FileParams {
+        version: 2,
+        name: "annual report".to_owned(),
+        ext: "pdf".to_owned(),
+    };
+
+    // Act
+    let web_route = params.to_web_route().unwrap();
+
+    // Assert
+    assert_eq!(
+        FileParams::route(),
+        ParameterizedRoute::new("/v{version:int}/files/{name}.{ext}")
+    );
+    assert_eq!(
+        web_route,
+        FileParams::route().to_web_route(&params).unwrap()
+    );
+    assert_eq!(&*web_route, "/v2/files/annual%20report.pdf");
+}
+
+#[test]
+fn derived_to_web_route_should_reject_empty_values() {
+    // Arrange
+    let named = ItemsParams {
+        id: String::new(),
+        page: 2,
+        sort: Some(String::new()),
+    };
+    let mixed = FileParams {
+        version: 2,
+        name: "report".to_owned(),
+        ext: String::new(),
+    };
+
+    // Act
+    let named_res = named.to_web_route();
+    let mixed_res = mixed.to_web_route();
+
+    // Assert
+    assert!(matches!(named_res, Err(WebRouteError::EmptyParamValue(param)) if param == "id"));
+    assert!(matches!(mixed_res, Err(WebRouteError::EmptyParamValue(param)) if param == "ext"));
+    assert!(matches!(
+        ItemsParams::route().to_web_route(&named),
+        Err(WebRouteError::EmptyParamValue(param)) if param == "id"
+    ));
+    assert!(matches!(
+        FileParams::route().to_web_route(&mixed),
+        Err(WebRouteError::EmptyParamValue(param)) if param == "ext"
+    ));
+}
+
+struct Owner {
+    id: u32,
+    repo: Repo,
+}
+
+struct Repo {
+    name: String,
+}
+
+#[derive(RouteParams)]
+#[route("/owners/{owner.id}/repos/{owner.repo.name}")]
+struct RepoParams {
+    owner: Owner,
+}
+
+#[test]
+fn derived_to_web_route_should_populate_dotted_params() {
+    // Arrange
+    let params = RepoParams {
+        owner: Owner {
+            id: 7,
+            repo: Repo {
+                name: "web route".to_owned(),
+            },
+        },
+    };
+
+    // Act
+    let web_route = params.to_web_route().unwrap();
+
+    // Assert
+    assert_eq!(
+        web_route,
+        RepoParams::route().to_web_route(&params).unwrap()
+    );
+    assert_eq!(&*web_route, "/owners/7/repos/web%20route");
+}
+
+/// A value with no `Display` implementation.
+struct Slug(Vec<&'static str>);
+
+impl web_route::ToRouteSegment for Slug {
+    fn to_route_segment(&self) -> String {
+        self.0.join("-")
+    }
+}
+
+#[derive(RouteParams)]
+#[route("/posts/{slug}{?page}")]
+struct PostParams {
+    slug: Slug,
+    page: Option<Slug>,
+}
+
+#[test]
+fn derived_to_web_route_should_populate_custom_route_segments() {
+    // Arrange
+    let params = PostParams {
+        slug: Slug(vec!["hello", "world"]),
+        page: Some(Slug(vec!["2"])),
+    };
+
+    // Act
+    let web_route = params.to_web_route().unwrap();
+
+    // Assert
+    assert_eq!(&*web_route, "/posts/hello-world?page=2");
+}
+
+#[derive(Debug, PartialEq, WebRoutes)]
+enum AppRoute {
+    #[route("/")]
+    Home,
+    #[route("/users/{name}")]
+    UserByName { name: String },
+    #[route("/users/{id:int}")]
+    User { id: u32 },
+    // Declared after the parameterized routes that also match it, which
+    // does not matter as static segments take priority.
+    #[route("/users/new")]
+    NewUser,
+    #[route("/reports/{year?}{?page}")]
+    Reports {
+        year: Option<u16>,
+        page: Option<u32>,
+    },
+    #[route("/files/{*path}")]
+    File { path: String },
+}
+
+#[test]
+fn derived_web_routes_should_return_variant_templates() {
+    // Act
+    let home = AppRoute::Home.template();
+    let user = AppRoute::User { id: 1 }.template();
+
+    // Assert
+    assert_eq!(home, ParameterizedRoute::new("/"));
+    assert_eq!(user, ParameterizedRoute::new("/users/{id:int}"));
+}
+
+#[test]
+fn derived_web_routes_should_populate_variant_templates() {
+    // Arrange
+    let routes = [
+        AppRoute::Home,
+        AppRoute::User { id: 42 },
+        AppRoute::UserByName {
+            name: "jane doe".to_owned(),
+        },
+        AppRoute::Reports {
+            year: Some(2024),
+            page: Some(2),
+        },
+        AppRoute::Reports {
+            year: None,
+            page: None,
+        },
+        AppRoute::File {
+            path: "a/b.txt".to_owned(),
+        },
+    ];
+
+    // Act
+    let web_routes = routes
+        .iter()
+        .map(|route| route.to_web_route().unwrap())
+        .collect::<Vec<_>>();
+
+    // Assert
+    assert_eq!(
+        web_routes,
+        vec![
+            WebRoute::new("/"),
+            WebRoute::new("/users/42"),
+            WebRoute::new("/users/jane%20doe"),
+            WebRoute::new("/reports/2024?page=2"),
+            WebRoute::new("/reports"),
+            WebRoute::new("/files/a/b.txt"),
+        ]
+    );
+}
+
+#[test]
+fn derived_web_routes_should_recognize_populated_routes() {
+    // Arrange
+    let routes = [
+        AppRoute::Home,
+        AppRoute::NewUser,
+        AppRoute::User { id: 42 },
+        AppRoute::UserByName {
+            name: "jane doe".to_owned(),
+        },
+        AppRoute::Reports {
+            year: Some(2024),
+            page: Some(2),
+        },
+        AppRoute::Reports {
+            year: None,
+            page: None,
+        },
+        AppRoute::File {
+            path: "a/b.txt".to_owned(),
+        },
+    ];
+
+    for route in routes {
+        // Act
+        let recognized = AppRoute::recognize(&route.to_web_route().unwrap());
+
+        // Assert
+        assert_eq!(recognized, Some(route));
+    }
+}
+
+#[test]
+fn derived_web_routes_should_not_populate_empty_fields() {
+    // Arrange
+    let route = AppRoute::UserByName {
+        name: String::new(),
+    };
+
+    // Act
+    let res = route.to_web_route();
+
+    // Assert
+    assert!(matches!(res, Err(WebRouteError::EmptyParamValue(param)) if param == "name"));
+    // The collapsed route would not have round-tripped to the variant.
+    assert_eq!(AppRoute::recognize(&WebRoute::new("/users")), None);
+}
+
+#[test]
+fn derived_web_routes_should_recognize_most_specific_variant() {
+    // Act
+    let new_user = AppRoute::recognize(&WebRoute::new("/users/new"));
+    let user = AppRoute::recognize(&WebRoute::new("/users/7"));
+    let unparsable_year = AppRoute::recognize(&WebRoute::new("/reports/last-year"));
+    let unknown = AppRoute::recognize(&WebRoute::new("/unknown"));
+
+    // Assert
+    assert_eq!(new_user, Some(AppRoute::NewUser));
+    assert_eq!(user, Some(AppRoute::User { id: 7 }));
+    assert_eq!(unparsable_year, None);
+    assert_eq!(unknown, None);
+}
+
+/// The `axum` integration tests, with routes built at compile time rather than
+/// in a `LazyLock`.
+mod axum_router {
+    use axum::{Json, Router, extract::Path, routing::get};
+    use fake::{Fake, Faker};
+    use web_route::{ParameterizedRoute, WebRoute, route};
+
+    static FOO_ROUTE: ParameterizedRoute = route!("/foo/{foo_id}");
+    static BAR_ROUTE: ParameterizedRoute = route!("/bar/{bar_id}");
+    static ROUTE_WITH_PATH: ParameterizedRoute = route!("/foo/{*path}");
+
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, fake::Dummy)]
+    struct NestedParams {
+        foo_id: String,
+        bar_id: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct PathParams {
+        path: WebRoute,
+    }
+
+    async fn nested_handler(Path(params): Path<NestedParams>) -> Json<NestedParams> {
+        Json(params)
+    }
+
+    async fn path_handler(Path(params): Path<PathParams>) -> Json<PathParams> {
+        Json(params)
+    }
+
+    #[tokio::test]
+    async fn compile_time_routes_should_nest_axum_routers() {
+        // Arrange
+        let path_params = Faker.fake::<NestedParams>();
+        let nested_router = Router::new().route(&BAR_ROUTE, get(nested_handler));
+        let router = Router::new().nest(&FOO_ROUTE, nested_router);
+
+        let test_server = axum_test::TestServer::new(router).unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &FOO_ROUTE
+                    .join(BAR_ROUTE.clone())
+                    .to_web_route(&path_params)
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        let parsed_body = response.json::<NestedParams>();
+        assert_eq!(parsed_body, path_params);
+    }
+
+    #[tokio::test]
+    async fn compile_time_routes_should_work_with_axum_path_extractor() {
+        // Arrange
+        let path_params = PathParams {
+            path: WebRoute::new("another/route"),
+        };
+        let router = Router::new().route(&ROUTE_WITH_PATH, get(path_handler));
+
+        let test_server = axum_test::TestServer::new(router).unwrap();
+
+        // Act
+        let response = test_server
+            .get(&ROUTE_WITH_PATH.to_web_route(&path_params).unwrap())
+            .await;
+
+        // Assert
+        let parsed_body = response.json::<PathParams>();
+        assert_eq!(parsed_body, path_params);
+    }
+}
@@ -0,0 +1,5 @@
+use web_route::{WebRoute, fixed_route};
+
+static ROUTE: WebRoute = fixed_route!("/users/{id}");
+
+fn main() {}
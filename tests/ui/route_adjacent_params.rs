@@ -0,0 +1,5 @@
+use web_route::{ParameterizedRoute, route};
+
+static ROUTE: ParameterizedRoute = route!("/files/{name}{ext}");
+
+fn main() {}
@@ -0,0 +1,5 @@
+use web_route::{ParameterizedRoute, route};
+
+static ROUTE: ParameterizedRoute = route!("/users/{id}/posts/{id}");
+
+fn main() {}
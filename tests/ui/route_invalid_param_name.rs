@@ -0,0 +1,5 @@
+use web_route::{ParameterizedRoute, route};
+
+static ROUTE: ParameterizedRoute = route!("/users/{user..id}");
+
+fn main() {}
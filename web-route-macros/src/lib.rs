@@ -0,0 +1,157 @@
+//! Procedural macros for the [`web-route`](https://docs.rs/web-route) crate.
+//!
+//! These are re-exported by `web-route` when its `macros` feature is enabled,
+//! and should not be depended on directly.
+
+mod route_params;
+mod template;
+mod web_routes;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitStr, parse_macro_input};
+
+/// Parses and normalizes a `ParameterizedRoute` template at compile time.
+///
+/// Malformed templates are rejected with a compiler error. The expansion is a
+/// `const` expression, so it can be used to define a `const` or `static`
+/// `ParameterizedRoute`.
+///
+/// ```ignore
+/// use web_route::{ParameterizedRoute, route};
+///
+/// static FOO_ROUTE: ParameterizedRoute = route!("/foo/{foo_id}");
+/// ```
+#[proc_macro]
+pub fn route(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+
+    match template::parse_parameterized(&literal.value()) {
+        Ok(template) => {
+            let normalized = template.to_normalized();
+            let path = template.to_path();
+
+            quote! {
+                ::web_route::__private::parameterized_route(#normalized, #path)
+            }
+            .into()
+        }
+        Err(err) => syn::Error::new(literal.span(), err)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Parses and normalizes a `WebRoute` at compile time.
+///
+/// Malformed routes are rejected with a compiler error. The expansion is a
+/// `const` expression, so it can be used to define a `const` or `static`
+/// `WebRoute`.
+///
+/// ```ignore
+/// use web_route::{WebRoute, fixed_route};
+///
+/// static FOO_ROUTE: WebRoute = fixed_route!("/foo/bar");
+/// ```
+#[proc_macro]
+pub fn fixed_route(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+
+    match template::normalize_web(&literal.value()) {
+        Ok(normalized) => quote! {
+            ::web_route::__private::fixed_route(#normalized)
+        }
+        .into(),
+        Err(err) => syn::Error::new(literal.span(), err)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Ties a struct holding route parameters to its `ParameterizedRoute` template.
+///
+/// It is checked at compile time that every parameter in the template has a
+/// matching field, and that every field is a parameter in the template. Each
+/// field needs to implement `ToRouteSegment`, which everything that implements
+/// `Display` does. A dotted parameter (e.g. `{user.id}`) is populated from a
+/// nested field.
+///
+/// Generates:
+///
+/// - `const fn route() -> ParameterizedRoute` returning the template.
+/// - `fn to_web_route(&self) -> Result<WebRoute, WebRouteError>` populating the
+///   template with the struct's fields, which only fails if the value of a path
+///   parameter is empty. Parameter constraints (e.g. `{id:int}`) are not
+///   checked, the field types are expected to uphold them. An optional
+///   parameter (e.g. `{year?}`) is omitted if its field is an `Option` that is
+///   `None`, along with any optional parameters after it.
+/// - Implementations of the `RouteParams` trait for the struct and for
+///   references to it, so that the struct can also populate other templates
+///   with the same parameters (e.g. via `ParameterizedRoute::to_web_route`).
+///   As references to `serde::Serialize` types already implement
+///   `RouteParams`, the struct cannot also implement `Serialize`.
+///
+/// ```ignore
+/// use web_route::RouteParams;
+///
+/// #[derive(RouteParams)]
+/// #[route("/foo/{foo_id}/bar/{bar_id}")]
+/// struct FooBarParams {
+///     foo_id: String,
+///     bar_id: u32,
+/// }
+/// ```
+#[proc_macro_derive(RouteParams, attributes(route))]
+pub fn derive_route_params(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    route_params::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Describes every route of an application with an enum, in which each
+/// variant has a `ParameterizedRoute` template and its fields are the
+/// template's parameters.
+///
+/// Each variant needs a `#[route("...")]` attribute, and either named fields or
+/// no fields. As with `#[derive(RouteParams)]`, it is checked at compile time
+/// that the fields and the template parameters line up. Each field needs to
+/// implement `ToRouteSegment` and `FromStr` (or be an `Option` of such a type
+/// for optional and query parameters). Dotted parameters (e.g. `{user.id}`)
+/// are not supported, and neither are variants whose templates match the same
+/// routes (e.g. `/users/{id}` and `/users/{name}`).
+///
+/// Generates:
+///
+/// - `const fn template(&self) -> ParameterizedRoute` returning the variant's
+///   template.
+/// - `fn to_web_route(&self) -> Result<WebRoute, WebRouteError>` populating
+///   the variant's template with its fields, which fails if the value of a path
+///   parameter is empty (it would otherwise not round-trip through
+///   `recognize`).
+/// - `fn recognize(route: &WebRoute) -> Option<Self>` returning the variant
+///   whose template matches `route`, if its fields can be parsed from the
+///   captured values. The templates are compiled into a `RouteMatcher` on
+///   first use, so static segments take priority over parameters regardless
+///   of the order of the variants.
+///
+/// ```ignore
+/// use web_route::WebRoutes;
+///
+/// #[derive(WebRoutes)]
+/// enum AppRoute {
+///     #[route("/")]
+///     Home,
+///     #[route("/users/{id:int}")]
+///     User { id: u32 },
+/// }
+/// ```
+#[proc_macro_derive(WebRoutes, attributes(route))]
+pub fn derive_web_routes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    web_routes::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
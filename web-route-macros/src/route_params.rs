@@ -0,0 +1,355 @@
+//! Implementation of `#[derive(RouteParams)]`.
+
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, Ident, LitStr, Type, ext::IdentExt,
+    spanned::Spanned,
+};
+
+use crate::template::{self, Part, Segment, Template};
+
+/// The expression accessing a field, to which the components of a dotted
+/// parameter name are appended.
+pub(crate) type FieldBase<'a> = &'a dyn Fn(&Field) -> TokenStream;
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let template = route_attribute(&input.attrs, &input.ident)?;
+    let fields = named_fields(&input)?;
+
+    let template_value = template.value();
+    let parsed_template = template::parse_parameterized(&template_value)
+        .map_err(|err| syn::Error::new(template.span(), err))?;
+    let normalized = parsed_template.to_normalized();
+    let path = parsed_template.to_path();
+
+    check_fields_match_params(&template, &parsed_template, &fields)?;
+
+    let field_base: FieldBase<'_> = &|field| {
+        let field_ident = &field.ident;
+        quote_spanned! {field.span()=> self.#field_ident }
+    };
+    let populated_route = populated_route(&parsed_template, &fields, field_base);
+
+    let params = parsed_template.params();
+    let param_arms = params.iter().map(|param| {
+        let value = optional_value(find_field(&fields, param), param, field_base);
+        quote! { #param => #value }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The [`ParameterizedRoute`](::web_route::ParameterizedRoute) that
+            /// this struct holds the parameters for.
+            pub const fn route() -> ::web_route::ParameterizedRoute {
+                ::web_route::__private::parameterized_route(#normalized, #path)
+            }
+
+            /// Populates the parameters of the route with the struct's fields.
+            ///
+            /// # Errors
+            ///
+            /// - [`WebRouteError::EmptyParamValue`](::web_route::error::WebRouteError::EmptyParamValue)
+            ///   if the value of a path parameter is empty.
+            pub fn to_web_route(
+                &self,
+            ) -> ::std::result::Result<::web_route::WebRoute, ::web_route::error::WebRouteError> {
+                #populated_route
+            }
+        }
+
+        impl #impl_generics ::web_route::RouteParams for #ident #ty_generics #where_clause {
+            fn param(
+                &self,
+                name: &str,
+            ) -> ::std::result::Result<
+                ::std::option::Option<::std::borrow::Cow<'_, str>>,
+                ::web_route::error::WebRouteError,
+            > {
+                let value: ::std::option::Option<&dyn ::web_route::ToRouteSegment> = match name {
+                    #(#param_arms,)*
+                    _ => ::std::option::Option::None,
+                };
+
+                ::std::result::Result::Ok(value.map(|value| {
+                    ::std::borrow::Cow::Owned(::web_route::ToRouteSegment::to_route_segment(value))
+                }))
+            }
+
+            fn param_names(
+                &self,
+            ) -> ::std::result::Result<
+                ::std::vec::Vec<::std::string::String>,
+                ::web_route::error::WebRouteError,
+            > {
+                ::std::result::Result::Ok(::std::vec![
+                    #(::std::string::String::from(#params)),*
+                ])
+            }
+        }
+
+        // So that the struct can also populate other routes by reference (e.g.
+        // `route.to_web_route(&params)`).
+        impl #impl_generics ::web_route::RouteParams for &#ident #ty_generics #where_clause {
+            fn param(
+                &self,
+                name: &str,
+            ) -> ::std::result::Result<
+                ::std::option::Option<::std::borrow::Cow<'_, str>>,
+                ::web_route::error::WebRouteError,
+            > {
+                ::web_route::RouteParams::param(*self, name)
+            }
+
+            fn param_names(
+                &self,
+            ) -> ::std::result::Result<
+                ::std::vec::Vec<::std::string::String>,
+                ::web_route::error::WebRouteError,
+            > {
+                ::web_route::RouteParams::param_names(*self)
+            }
+        }
+    })
+}
+
+/// Generates the expression populating `parsed_template` with the values of
+/// `fields`, as a `Result<WebRoute, WebRouteError>`.
+pub(crate) fn populated_route(
+    parsed_template: &Template<'_>,
+    fields: &[&Field],
+    field_base: FieldBase<'_>,
+) -> TokenStream {
+    let populated_segments = parsed_template
+        .segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::NamedParam {
+                name: param,
+                optional: true,
+                ..
+            } => {
+                let field = find_field(fields, param);
+                let value = optional_value(field, param, field_base);
+                quote_spanned! {field.span()=>
+                    ::web_route::__private::PopulatedSegment::OptionalParam(#param, #value)
+                }
+            }
+            Segment::NamedParam { name: param, .. } => {
+                let field = find_field(fields, param);
+                let value = field_access(field, param, field_base);
+                quote_spanned! {field.span()=>
+                    ::web_route::__private::PopulatedSegment::NamedParam(#param, &#value)
+                }
+            }
+            Segment::CatchallParam(param) => {
+                let field = find_field(fields, param);
+                let value = field_access(field, param, field_base);
+                quote_spanned! {field.span()=>
+                    ::web_route::__private::PopulatedSegment::CatchallParam(&#value)
+                }
+            }
+            Segment::Static(value) => quote! {
+                ::web_route::__private::PopulatedSegment::Static(#value)
+            },
+            Segment::Mixed(parts) => {
+                let populated_parts = parts.iter().map(|part| match part {
+                    Part::Param { name: param, .. } => {
+                        let field = find_field(fields, param);
+                        let value = field_access(field, param, field_base);
+                        quote_spanned! {field.span()=>
+                            ::web_route::__private::PopulatedSegment::NamedParam(#param, &#value)
+                        }
+                    }
+                    Part::Static(value) => quote! {
+                        ::web_route::__private::PopulatedSegment::Static(#value)
+                    },
+                });
+
+                quote! {
+                    ::web_route::__private::PopulatedSegment::Mixed(&[#(#populated_parts),*])
+                }
+            }
+        });
+
+    // Query parameters are optional, so `Option` fields are omitted when they
+    // are `None`.
+    let populated_query_params = parsed_template.query_params.iter().map(|param| {
+        let value = optional_value(find_field(fields, param), param, field_base);
+        quote! { (#param, #value) }
+    });
+
+    quote! {
+        ::web_route::__private::populated_route(
+            &[#(#populated_segments),*],
+            &[#(#populated_query_params),*],
+        )
+    }
+}
+
+/// Extracts the template from the `#[route("...")]` attribute of `ident`.
+pub(crate) fn route_attribute(attrs: &[Attribute], ident: &Ident) -> syn::Result<LitStr> {
+    let mut route_attributes = attrs.iter().filter(|attr| attr.path().is_ident("route"));
+
+    let attr = route_attributes.next().ok_or_else(|| {
+        syn::Error::new(
+            ident.span(),
+            "missing `#[route(\"...\")]` attribute defining the route template",
+        )
+    })?;
+
+    if let Some(duplicate) = route_attributes.next() {
+        return Err(syn::Error::new(
+            duplicate.span(),
+            "only one `#[route(\"...\")]` attribute is allowed",
+        ));
+    }
+
+    attr.parse_args()
+}
+
+/// The struct's named fields.
+fn named_fields(input: &DeriveInput) -> syn::Result<Vec<&Field>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            Fields::Unnamed(_) | Fields::Unit => Err(syn::Error::new(
+                input.ident.span(),
+                "`RouteParams` can only be derived for structs with named fields",
+            )),
+        },
+        Data::Enum(_) | Data::Union(_) => Err(syn::Error::new(
+            input.ident.span(),
+            "`RouteParams` can only be derived for structs",
+        )),
+    }
+}
+
+/// The field holding the value of `param`, which is the first component of a
+/// dotted parameter name (e.g. `user` for `{user.id}`).
+fn find_field<'a>(fields: &[&'a Field], param: &str) -> &'a Field {
+    fields
+        .iter()
+        .find(|field| field_name(field) == root_name(param))
+        .expect("fields should have been checked against the template params")
+}
+
+/// The first component of a (possibly dotted) parameter name.
+pub(crate) fn root_name(param: &str) -> &str {
+    param.split('.').next().unwrap_or(param)
+}
+
+/// The expression accessing the value of `param`, following the components of
+/// a dotted parameter name into nested fields (e.g. `self.user.id` for
+/// `{user.id}`).
+fn field_access(field: &Field, param: &str, field_base: FieldBase<'_>) -> TokenStream {
+    let base = field_base(field);
+    let nested_members = param
+        .split('.')
+        .skip(1)
+        .map(|component| match component.parse::<u32>() {
+            Ok(index) => syn::Member::Unnamed(syn::Index {
+                index,
+                span: field.span(),
+            }),
+            Err(_) => syn::Member::Named(syn::Ident::new(component, field.span())),
+        });
+
+    quote_spanned! {field.span()=> #base #(.#nested_members)* }
+}
+
+/// The name of a named field, without any `r#` prefix.
+pub(crate) fn field_name(field: &Field) -> String {
+    field
+        .ident
+        .as_ref()
+        .map(|ident| ident.unraw().to_string())
+        .unwrap_or_default()
+}
+
+/// The value of a field that populates an optional parameter, as an
+/// `Option<&dyn ToRouteSegment>` that is `None` if the field is an `Option`
+/// that is `None`.
+///
+/// The type of a nested field (e.g. for `{user.id}`) is not known, so it is
+/// always treated as having a value.
+fn optional_value(field: &Field, param: &str, field_base: FieldBase<'_>) -> TokenStream {
+    let value = field_access(field, param, field_base);
+
+    if param == root_name(param) && is_option(&field.ty) {
+        quote_spanned! {field.span()=>
+            #value
+                .as_ref()
+                .map(|value| value as &dyn ::web_route::ToRouteSegment)
+        }
+    } else {
+        quote_spanned! {field.span()=>
+            ::std::option::Option::Some(&#value as &dyn ::web_route::ToRouteSegment)
+        }
+    }
+}
+
+/// Whether `ty` is (syntactically) an `Option`.
+pub(crate) fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Ensures that every parameter in the template has a matching field, and that
+/// every field is used by a parameter in the template.
+pub(crate) fn check_fields_match_params(
+    template: &LitStr,
+    parsed_template: &Template<'_>,
+    fields: &[&Field],
+) -> syn::Result<()> {
+    let params = parsed_template.params();
+    let field_names = fields
+        .iter()
+        .map(|field| field_name(field))
+        .collect::<HashSet<_>>();
+
+    let missing_fields = params
+        .iter()
+        .filter(|param| !field_names.contains(root_name(param)))
+        .map(|param| {
+            syn::Error::new(
+                template.span(),
+                format!("route parameter `{param}` has no matching field"),
+            )
+        });
+    let unused_fields = fields
+        .iter()
+        .filter(|field| {
+            !params
+                .iter()
+                .any(|param| root_name(param) == field_name(field))
+        })
+        .map(|field| {
+            syn::Error::new(
+                field.span(),
+                format!(
+                    "field `{}` is not a parameter in the route template",
+                    field_name(field)
+                ),
+            )
+        });
+
+    missing_fields
+        .chain(unused_fields)
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        })
+        .map_or(Ok(()), Err)
+}
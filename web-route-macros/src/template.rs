@@ -0,0 +1,797 @@
+//! Compile-time parsing and normalization of routes.
+//!
+//! This mirrors the strict runtime parsing of `ParameterizedRoute::parse` and
+//! `WebRoute::parse` in `web-route`, rejecting malformed input rather than
+//! working around it like `new` does. It needs to be kept in sync with them,
+//! which `tests/macros.rs` in `web-route` checks for every construct of the
+//! grammar, and `tests/compile_fail.rs` for the rejected ones.
+
+/// A parsed segment of a `ParameterizedRoute` template.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment<'a> {
+    NamedParam {
+        name: &'a str,
+        constraint: Option<&'a str>,
+        optional: bool,
+    },
+    CatchallParam(&'a str),
+    Static(&'a str),
+    /// Static text mixed with parameters (e.g. `{name}.{ext}`).
+    Mixed(Vec<Part<'a>>),
+}
+
+/// A part of a [`Segment::Mixed`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Part<'a> {
+    Param {
+        name: &'a str,
+        constraint: Option<&'a str>,
+    },
+    Static(&'a str),
+}
+
+impl Part<'_> {
+    fn to_template(&self) -> String {
+        match self {
+            Part::Param {
+                name,
+                constraint: Some(constraint),
+            } => format!("{{{name}:{constraint}}}"),
+            _ => self.to_path(),
+        }
+    }
+
+    fn to_path(&self) -> String {
+        match self {
+            Part::Param { name, .. } => format!("{{{name}}}"),
+            Part::Static(value) => (*value).to_owned(),
+        }
+    }
+
+    fn to_shape(&self) -> String {
+        match self {
+            Part::Param { constraint, .. } => format!("{{:{}}}", constraint.unwrap_or_default()),
+            Part::Static(value) => (*value).to_owned(),
+        }
+    }
+}
+
+impl Segment<'_> {
+    /// The names of the parameters in the segment.
+    pub(crate) fn params(&self) -> Vec<&str> {
+        match self {
+            Segment::NamedParam { name: param, .. } | Segment::CatchallParam(param) => {
+                vec![param]
+            }
+            Segment::Static(_) => Vec::new(),
+            Segment::Mixed(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    Part::Param { name, .. } => Some(*name),
+                    Part::Static(_) => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether the segment is an optional named parameter.
+    pub(crate) fn is_optional(&self) -> bool {
+        matches!(self, Segment::NamedParam { optional: true, .. })
+    }
+
+    fn to_template(&self) -> String {
+        match self {
+            Segment::NamedParam {
+                name,
+                constraint,
+                optional,
+            } => {
+                let optional = if *optional { "?" } else { "" };
+                match constraint {
+                    Some(constraint) => format!("{{{name}{optional}:{constraint}}}"),
+                    None => format!("{{{name}{optional}}}"),
+                }
+            }
+            Segment::Mixed(parts) => parts.iter().map(Part::to_template).collect(),
+            _ => self.to_path(),
+        }
+    }
+
+    /// Renders the segment without the names of its parameters, so that
+    /// segments matching the same values have the same shape.
+    fn to_shape(&self) -> String {
+        match self {
+            Segment::NamedParam { constraint, .. } => {
+                format!("{{:{}}}", constraint.unwrap_or_default())
+            }
+            Segment::CatchallParam(_) => "{*}".to_owned(),
+            Segment::Static(value) => (*value).to_owned(),
+            Segment::Mixed(parts) => parts.iter().map(Part::to_shape).collect(),
+        }
+    }
+
+    /// Renders the segment in the syntax of axum, which has no constraints or
+    /// optional parameters.
+    fn to_path(&self) -> String {
+        match self {
+            Segment::NamedParam { name, .. } => format!("{{{name}}}"),
+            Segment::CatchallParam(param) => format!("{{*{param}}}"),
+            Segment::Static(value) => (*value).to_owned(),
+            Segment::Mixed(parts) => parts.iter().map(Part::to_path).collect(),
+        }
+    }
+}
+
+/// A parsed `ParameterizedRoute` template.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Template<'a> {
+    pub(crate) segments: Vec<Segment<'a>>,
+    pub(crate) query_params: Vec<&'a str>,
+}
+
+impl Template<'_> {
+    /// The names of the path parameters, followed by those of the query
+    /// parameters.
+    pub(crate) fn params(&self) -> Vec<&str> {
+        self.segments
+            .iter()
+            .flat_map(Segment::params)
+            .chain(self.query_params.iter().copied())
+            .collect()
+    }
+
+    /// The shapes of the paths of the template (see [`Segment::to_shape`]),
+    /// one for each of its forms with and without its optional parameters.
+    /// Templates that share a shape match the same routes, mirroring how a
+    /// `RouteMatcher` detects ambiguous routes.
+    pub(crate) fn to_shapes(&self) -> Vec<String> {
+        let required = self
+            .segments
+            .iter()
+            .take_while(|segment| !segment.is_optional())
+            .count();
+
+        (required..=self.segments.len())
+            .map(|len| join_segments(self.segments[..len].iter().map(Segment::to_shape)))
+            .collect()
+    }
+
+    /// Renders the path of the template in the syntax of axum, which is what
+    /// a `ParameterizedRoute` derefs to.
+    pub(crate) fn to_path(&self) -> String {
+        join_segments(self.segments.iter().map(Segment::to_path))
+    }
+
+    /// Renders the template in the representation that
+    /// `ParameterizedRoute::new` would produce.
+    pub(crate) fn to_normalized(&self) -> String {
+        let path = join_segments(self.segments.iter().map(Segment::to_template));
+
+        if self.query_params.is_empty() {
+            path
+        } else {
+            format!("{path}{{?{}}}", self.query_params.join(","))
+        }
+    }
+}
+
+/// Parses a `ParameterizedRoute` template into its segments and query
+/// parameters.
+///
+/// # Errors
+///
+/// A description of the problem if the template is malformed.
+pub(crate) fn parse_parameterized(route: &str) -> Result<Template<'_>, String> {
+    let (route, query_params) = match route.split_once("{?") {
+        Some((path, query)) => (path, parse_query_params(query)?),
+        None => (route, Vec::new()),
+    };
+
+    let segments = split_segments(route)
+        .map(|(position, segment)| {
+            parse_parameterized_segment(segment)
+                .map_err(|reason| invalid_segment(position, segment, &reason))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let template = Template {
+        segments,
+        query_params,
+    };
+    validate_structure(&template)?;
+
+    Ok(template)
+}
+
+/// Ensures that there is at most one catch-all parameter, that it is the final
+/// segment, that optional parameters are only followed by other optional
+/// parameters and that no parameter name is declared more than once.
+fn validate_structure(template: &Template<'_>) -> Result<(), String> {
+    let catchall_params = template
+        .segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::CatchallParam(param) => Some(*param),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if catchall_params.len() > 1 {
+        return Err(format!(
+            "only one catch-all parameter is allowed, found: {}",
+            catchall_params.join(", ")
+        ));
+    }
+
+    if let Some(param) = catchall_params.first()
+        && !matches!(template.segments.last(), Some(Segment::CatchallParam(_)))
+    {
+        return Err(format!(
+            "catch-all parameter `{param}` needs to be the final segment"
+        ));
+    }
+
+    if let Some(first_optional) = template.segments.iter().position(Segment::is_optional)
+        && !template.segments[first_optional..]
+            .iter()
+            .all(Segment::is_optional)
+    {
+        return Err(format!(
+            "optional parameter `{}` can only be followed by other optional parameters",
+            template.segments[first_optional].params().concat()
+        ));
+    }
+
+    let params = template.params();
+    let mut duplicate_params = Vec::new();
+    for (i, param) in params.iter().enumerate() {
+        if params[..i].contains(param) && !duplicate_params.contains(param) {
+            duplicate_params.push(*param);
+        }
+    }
+
+    if duplicate_params.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "parameters are declared more than once: {}",
+            duplicate_params.join(", ")
+        ))
+    }
+}
+
+/// Parses the comma separated query parameter names that follow a `{?`.
+fn parse_query_params(query: &str) -> Result<Vec<&str>, String> {
+    let params = query.strip_suffix('}').ok_or_else(|| {
+        "query parameters need to be closed by a `}` at the end of the template".to_owned()
+    })?;
+
+    let mut unique_params = Vec::new();
+    for param in params.split(',').map(str::trim) {
+        validate_param_name(param)
+            .map_err(|reason| format!("invalid query parameter `{param}`: {reason}"))?;
+
+        if !unique_params.contains(&param) {
+            unique_params.push(param);
+        }
+    }
+
+    Ok(unique_params)
+}
+
+/// Normalizes a `WebRoute` into the representation that `WebRoute::new` would
+/// produce.
+///
+/// # Errors
+///
+/// A description of the problem if the route is malformed.
+pub(crate) fn normalize_web(route: &str) -> Result<String, String> {
+    let (route, query) = match route.split_once('?') {
+        Some((path, query)) => (path, (!query.is_empty()).then_some(query)),
+        None => (route, None),
+    };
+
+    let segments = split_segments(route)
+        .map(|(position, segment)| {
+            validate_web_segment(segment)
+                .map(|_| segment)
+                .map_err(|reason| invalid_segment(position, segment, &reason))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match query {
+        Some(query) => {
+            validate_query(query).map_err(|reason| format!("invalid query `{query}`: {reason}"))?;
+            Ok(format!("{}?{query}", join_segments(segments)))
+        }
+        None => Ok(join_segments(segments)),
+    }
+}
+
+/// Splits `route` into its non-empty segments, along with their position.
+fn split_segments(route: &str) -> impl Iterator<Item = (usize, &str)> {
+    route
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .enumerate()
+}
+
+/// Joins `segments` back together with single `/` separators.
+fn join_segments<S: AsRef<str>>(segments: impl IntoIterator<Item = S>) -> String {
+    let segments = segments
+        .into_iter()
+        .map(|segment| segment.as_ref().to_owned())
+        .collect::<Vec<_>>();
+
+    format!("/{}", segments.join("/"))
+}
+
+fn invalid_segment(position: usize, segment: &str, reason: &str) -> String {
+    format!("invalid segment `{segment}` at position {position}: {reason}")
+}
+
+fn parse_parameterized_segment(segment: &str) -> Result<Segment<'_>, String> {
+    let parts = split_params(segment).ok_or_else(|| {
+        "unbalanced braces, a parameter needs to be enclosed in `{` and `}`".to_owned()
+    })?;
+
+    match parts.as_slice() {
+        [RawPart::Param(param)] => match param.strip_prefix('*') {
+            Some(param) => validate_param_name(param).map(|_| Segment::CatchallParam(param)),
+            None => {
+                let (name, constraint) = split_constraint(param)?;
+                let (name, optional) = match name.strip_suffix('?') {
+                    Some(name) => (name, true),
+                    None => (name, false),
+                };
+
+                validate_param_name(name).map(|_| Segment::NamedParam {
+                    name,
+                    constraint,
+                    optional,
+                })
+            }
+        },
+        [RawPart::Static(value)] => validate_web_segment(value).map(|_| Segment::Static(value)),
+        parts => {
+            if parts
+                .windows(2)
+                .any(|pair| matches!(pair, [RawPart::Param(_), RawPart::Param(_)]))
+            {
+                return Err(
+                    "parameters need to be separated by static text within a segment".to_owned(),
+                );
+            }
+
+            parts
+                .iter()
+                .map(|part| match part {
+                    RawPart::Param(param) if param.starts_with('*') => {
+                        Err("a catch-all parameter needs to span the whole segment".to_owned())
+                    }
+                    RawPart::Param(param) => {
+                        let (name, constraint) = split_constraint(param)?;
+                        validate_param_name(name).map(|_| Part::Param { name, constraint })
+                    }
+                    RawPart::Static(value) => {
+                        validate_web_segment(value).map(|_| Part::Static(value))
+                    }
+                })
+                .collect::<Result<_, _>>()
+                .map(Segment::Mixed)
+        }
+    }
+}
+
+/// A part of a segment, before its parameters have been parsed.
+enum RawPart<'a> {
+    Static(&'a str),
+    /// The contents of a parameter, without its braces.
+    Param(&'a str),
+}
+
+/// Splits `segment` into static text and the contents of its parameters.
+/// Braces nested within a parameter (e.g. the `{3}` of `{id:[0-9]{3}}`) are
+/// kept as part of it.
+///
+/// Returns [`None`] if the braces are unbalanced.
+fn split_params(segment: &str) -> Option<Vec<RawPart<'_>>> {
+    let mut parts = Vec::new();
+    let mut remaining = segment;
+
+    while let Some(start) = remaining.find(['{', '}']) {
+        if remaining[start..].starts_with('}') {
+            return None;
+        }
+
+        if start > 0 {
+            parts.push(RawPart::Static(&remaining[..start]));
+        }
+
+        let mut depth = 0;
+        let len = remaining[start..].char_indices().find_map(|(index, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+
+            (depth == 0).then_some(index + 1)
+        })?;
+
+        parts.push(RawPart::Param(&remaining[start + 1..start + len - 1]));
+        remaining = &remaining[start + len..];
+    }
+
+    if !remaining.is_empty() {
+        parts.push(RawPart::Static(remaining));
+    }
+
+    Some(parts)
+}
+
+/// Splits the contents of a parameter into its name and validated
+/// constraint.
+fn split_constraint(param: &str) -> Result<(&str, Option<&str>), String> {
+    match param.split_once(':') {
+        Some((name, constraint)) => Ok((name, Some(validate_constraint(constraint)?))),
+        None => Ok((param, None)),
+    }
+}
+
+/// Ensures that `constraint` is a built-in constraint, or a valid regex if
+/// the `regex` feature is enabled. Bare words that are not built-in
+/// constraints are rejected, as they are most likely misspelled.
+fn validate_constraint(constraint: &str) -> Result<&str, String> {
+    let is_valid = match constraint {
+        "int" | "uuid" | "slug" => true,
+        word if word.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') => false,
+        #[cfg(feature = "regex")]
+        pattern => regex::Regex::new(&format!("^(?:{pattern})$")).is_ok(),
+        #[cfg(not(feature = "regex"))]
+        _ => false,
+    };
+
+    if is_valid {
+        Ok(constraint)
+    } else {
+        Err(format!(
+            "unknown constraint `{constraint}`, expected `int`, `uuid`, `slug` or (with the `regex` feature) a valid regex that is not a bare word"
+        ))
+    }
+}
+
+fn validate_param_name(param: &str) -> Result<(), String> {
+    if param.is_empty() {
+        return Err("parameter name is empty".to_owned());
+    }
+
+    // Dots separate the components of a nested name (e.g. `user.id`), so
+    // none of them can be empty.
+    if param.split('.').any(str::is_empty) {
+        return Err("invalid character `.` in parameter name".to_owned());
+    }
+
+    match param
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.')))
+    {
+        Some(c) => Err(format!("invalid character `{c}` in parameter name")),
+        None => Ok(()),
+    }
+}
+
+fn validate_web_segment(segment: &str) -> Result<(), String> {
+    validate_chars(segment, &['{', '}', '?', '#'])
+}
+
+fn validate_query(query: &str) -> Result<(), String> {
+    validate_chars(query, &['#'])
+}
+
+/// Ensures that `value` does not contain any whitespace, control or `invalid`
+/// characters, and that any percent-encoding is well formed.
+fn validate_chars(value: &str, invalid: &[char]) -> Result<(), String> {
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => {
+                let is_valid_escape = chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+                    && chars.next().is_some_and(|c| c.is_ascii_hexdigit());
+
+                if !is_valid_escape {
+                    return Err("`%` needs to be followed by two hex digits".to_owned());
+                }
+            }
+            c if invalid.contains(&c) => return Err(format!("invalid character `{c}`")),
+            c if c.is_whitespace() || c.is_control() => {
+                return Err(format!("invalid character `{}`", c.escape_default()));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod template_tests {
+    use super::*;
+
+    mod parse_parameterized {
+        use super::*;
+
+        #[test]
+        fn should_parse_each_segment_type() {
+            // Act
+            let template = parse_parameterized("/foo/{foo_id}/{*rest}").unwrap();
+
+            // Assert
+            assert_eq!(
+                template.segments,
+                vec![
+                    Segment::Static("foo"),
+                    Segment::NamedParam {
+                        name: "foo_id",
+                        constraint: None,
+                        optional: false,
+                    },
+                    Segment::CatchallParam("rest"),
+                ]
+            );
+        }
+
+        #[test]
+        fn should_parse_query_params() {
+            // Act
+            let template = parse_parameterized("/items{?page, per_page}").unwrap();
+
+            // Assert
+            assert_eq!(template.segments, vec![Segment::Static("items")]);
+            assert_eq!(template.query_params, vec!["page", "per_page"]);
+        }
+
+        #[test]
+        fn should_reject_duplicate_params() {
+            // Act
+            let path = parse_parameterized("/{id}/x/{id}");
+            let query = parse_parameterized("/{id}{?id}");
+
+            // Assert
+            assert!(path.is_err());
+            assert!(query.is_err());
+        }
+
+        #[test]
+        fn should_reject_misplaced_catchall() {
+            // Act
+            let misplaced = parse_parameterized("/{*rest}/more");
+            let multiple = parse_parameterized("/{*first}/{*second}");
+
+            // Assert
+            assert!(misplaced.is_err());
+            assert!(multiple.is_err());
+        }
+
+        #[test]
+        fn should_parse_optional_params() {
+            // Act
+            let template = parse_parameterized("/reports/{year?:int}/{month?}").unwrap();
+
+            // Assert
+            assert!(template.segments[1..].iter().all(Segment::is_optional));
+            assert_eq!(template.to_normalized(), "/reports/{year?:int}/{month?}");
+            assert_eq!(template.to_path(), "/reports/{year}/{month}");
+        }
+
+        #[test]
+        fn should_reject_misplaced_optional_param() {
+            // Act
+            let static_segment = parse_parameterized("/reports/{year?}/summary");
+            let required_param = parse_parameterized("/reports/{year?}/{month}");
+
+            // Assert
+            assert!(static_segment.is_err());
+            assert!(required_param.is_err());
+        }
+
+        #[test]
+        fn should_parse_mixed_segments() {
+            // Act
+            let template = parse_parameterized("/v{version:int}/{name}.{ext}").unwrap();
+
+            // Assert
+            assert_eq!(template.params(), vec!["version", "name", "ext"]);
+            assert_eq!(template.to_normalized(), "/v{version:int}/{name}.{ext}");
+            assert_eq!(template.to_path(), "/v{version}/{name}.{ext}");
+        }
+
+        #[test]
+        fn should_reject_ambiguous_mixed_segments() {
+            // Act
+            let adjacent = parse_parameterized("/{name}{ext}");
+            let catchall = parse_parameterized("/{*path}.json");
+
+            // Assert
+            assert!(adjacent.is_err());
+            assert!(catchall.is_err());
+        }
+
+        #[test]
+        fn should_reject_unknown_constraint_words() {
+            // Act
+            let whole = parse_parameterized("/users/{id:Int}");
+            let partial = parse_parameterized("/files/{name}.{ext:extension}");
+
+            // Assert
+            assert!(whole.is_err());
+            assert!(partial.is_err());
+        }
+
+        #[test]
+        fn should_shape_each_form_without_param_names() {
+            // Arrange
+            let template =
+                parse_parameterized("/users/{id:int}/{name}.{ext}/{year?}{?page}").unwrap();
+            let renamed = parse_parameterized("/users/{user_id:int}/{file}.{format}").unwrap();
+
+            // Act
+            let shapes = template.to_shapes();
+
+            // Assert
+            assert_eq!(
+                shapes,
+                vec!["/users/{:int}/{:}.{:}", "/users/{:int}/{:}.{:}/{:}"]
+            );
+            assert_eq!(renamed.to_shapes(), vec![shapes[0].clone()]);
+        }
+
+        #[test]
+        fn should_reject_unclosed_query_params() {
+            // Act
+            let res = parse_parameterized("/items{?page/foo");
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    mod normalize_parameterized {
+        use super::*;
+
+        fn normalize_parameterized(route: &str) -> Result<String, String> {
+            parse_parameterized(route).map(|template| template.to_normalized())
+        }
+
+        #[test]
+        fn should_normalize_slashes() {
+            // Act
+            let normalized = normalize_parameterized("foo//{foo_id}/{*rest}/").unwrap();
+
+            // Assert
+            assert_eq!(normalized, "/foo/{foo_id}/{*rest}");
+        }
+
+        #[test]
+        fn should_reject_unbalanced_braces() {
+            // Act
+            let res = normalize_parameterized("/foo/{bar/baz}");
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_reject_empty_parameter_names() {
+            // Act
+            let named = normalize_parameterized("/{}/x");
+            let catchall = normalize_parameterized("/x/{*}");
+
+            // Assert
+            assert!(named.is_err());
+            assert!(catchall.is_err());
+        }
+
+        #[test]
+        fn should_keep_constraints_out_of_path() {
+            // Act
+            let template = parse_parameterized("/users/{id:int}/posts/{slug:slug}").unwrap();
+
+            // Assert
+            assert_eq!(
+                template.to_normalized(),
+                "/users/{id:int}/posts/{slug:slug}"
+            );
+            assert_eq!(template.to_path(), "/users/{id}/posts/{slug}");
+        }
+
+        #[cfg(not(feature = "regex"))]
+        #[test]
+        fn should_reject_custom_pattern_without_regex_feature() {
+            // Act
+            let res = normalize_parameterized("/{code:[A-Z]+}");
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_reject_invalid_parameter_names() {
+            // Act
+            let res = normalize_parameterized("/{foo-id}");
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_accept_dotted_parameter_names() {
+            // Act
+            let dotted = normalize_parameterized("/{user.id}");
+            let empty_component = normalize_parameterized("/{user..id}");
+
+            // Assert
+            assert!(dotted.is_ok());
+            assert!(empty_component.is_err());
+        }
+    }
+
+    mod normalize_web {
+        use super::*;
+
+        #[test]
+        fn should_normalize_slashes() {
+            // Act
+            let normalized = normalize_web("foo//bar%20baz/").unwrap();
+
+            // Assert
+            assert_eq!(normalized, "/foo/bar%20baz");
+        }
+
+        #[test]
+        fn should_preserve_query() {
+            // Act
+            let normalized = normalize_web("search//items/?q=a/b").unwrap();
+
+            // Assert
+            assert_eq!(normalized, "/search/items?q=a/b");
+        }
+
+        #[test]
+        fn should_reject_fragment() {
+            // Act
+            let res = normalize_web("/search?q=x#results");
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_reject_whitespace() {
+            // Act
+            let res = normalize_web("/foo bar");
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_reject_invalid_percent_encoding() {
+            // Act
+            let res = normalize_web("/100%");
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_reject_braces() {
+            // Act
+            let res = normalize_web("/{foo}");
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+}
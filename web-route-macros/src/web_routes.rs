@@ -0,0 +1,252 @@
+//! Implementation of `#[derive(WebRoutes)]`.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{Data, DeriveInput, Field, Fields, LitStr, Variant, spanned::Spanned};
+
+use crate::{
+    route_params::{
+        check_fields_match_params, field_name, is_option, populated_route, root_name,
+        route_attribute,
+    },
+    template::{self, Template},
+};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let variants = match &input.data {
+        Data::Enum(data) => data.variants.iter().collect::<Vec<_>>(),
+        Data::Struct(_) | Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`WebRoutes` can only be derived for enums",
+            ));
+        }
+    };
+
+    // The templates are collected up front, so that the parsed templates can
+    // borrow from them.
+    let templates = variants
+        .iter()
+        .map(|variant| {
+            let template = route_attribute(&variant.attrs, &variant.ident)?;
+            let template_value = template.value();
+            Ok((*variant, template, template_value))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut routes = Vec::new();
+    let mut errors = Vec::<syn::Error>::new();
+    for (variant, template, template_value) in &templates {
+        match VariantRoute::parse(variant, template, template_value) {
+            Ok(route) => routes.push(route),
+            Err(err) => errors.push(err),
+        }
+    }
+    errors.extend(ambiguous_routes(&routes));
+    if let Some(err) = errors.into_iter().reduce(|mut combined, err| {
+        combined.combine(err);
+        combined
+    }) {
+        return Err(err);
+    }
+
+    let template_arms = routes.iter().map(|route| {
+        let pattern = route.pattern(false);
+        let template = route.template();
+        quote! { #pattern => #template }
+    });
+
+    let populated_arms = routes.iter().map(|route| {
+        let pattern = route.pattern(true);
+        // The fields are bound by reference in the pattern.
+        let populated_route = populated_route(&route.parsed_template, &route.fields, &|field| {
+            let field_ident = &field.ident;
+            quote_spanned! {field.span()=> (*#field_ident) }
+        });
+        quote! { #pattern => #populated_route }
+    });
+
+    let templates = routes.iter().map(VariantRoute::template);
+    let recognized_arms = routes.iter().enumerate().map(|(index, route)| {
+        let variant = route.recognized_variant();
+        quote! { #index => ::std::option::Option::Some(#variant) }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The [`ParameterizedRoute`](::web_route::ParameterizedRoute)
+            /// template of the variant.
+            pub const fn template(&self) -> ::web_route::ParameterizedRoute {
+                match self {
+                    #(#template_arms,)*
+                }
+            }
+
+            /// Populates the template of the variant with its fields.
+            ///
+            /// # Errors
+            ///
+            /// - [`WebRouteError::EmptyParamValue`](::web_route::error::WebRouteError::EmptyParamValue)
+            ///   if the value of a path parameter is empty.
+            pub fn to_web_route(
+                &self,
+            ) -> ::std::result::Result<::web_route::WebRoute, ::web_route::error::WebRouteError> {
+                match self {
+                    #(#populated_arms,)*
+                }
+            }
+
+            /// Returns the variant whose template matches `route`, with its
+            /// fields parsed from the captured parameter values. Static
+            /// segments take priority over parameters, as with
+            /// [`RouteMatcher`](::web_route::RouteMatcher).
+            pub fn recognize(route: &::web_route::WebRoute) -> ::std::option::Option<Self> {
+                static MATCHER: ::std::sync::LazyLock<::web_route::RouteMatcher<usize>> =
+                    ::std::sync::LazyLock::new(|| {
+                        ::web_route::__private::route_matcher([#(#templates),*])
+                    });
+
+                let matched = MATCHER.at(route)?;
+                let params = &matched.params;
+
+                match *matched.value {
+                    #(#recognized_arms,)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+/// A variant of the enum along with its parsed `#[route("...")]` template.
+struct VariantRoute<'a> {
+    variant: &'a Variant,
+    fields: Vec<&'a Field>,
+    normalized: String,
+    path: String,
+    parsed_template: Template<'a>,
+}
+
+impl<'a> VariantRoute<'a> {
+    fn parse(
+        variant: &'a Variant,
+        template: &LitStr,
+        template_value: &'a str,
+    ) -> syn::Result<Self> {
+        let fields = match &variant.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new(
+                    variant.ident.span(),
+                    "`WebRoutes` variants need to have named fields or no fields",
+                ));
+            }
+        };
+
+        let parsed_template = template::parse_parameterized(template_value)
+            .map_err(|err| syn::Error::new(template.span(), err))?;
+
+        if let Some(param) = parsed_template
+            .params()
+            .into_iter()
+            .find(|param| *param != root_name(param))
+        {
+            return Err(syn::Error::new(
+                template.span(),
+                format!(
+                    "dotted parameter `{param}` is not supported, as a nested field cannot be recognized from a route"
+                ),
+            ));
+        }
+
+        check_fields_match_params(template, &parsed_template, &fields)?;
+
+        Ok(Self {
+            variant,
+            fields,
+            normalized: parsed_template.to_normalized(),
+            path: parsed_template.to_path(),
+            parsed_template,
+        })
+    }
+
+    /// The pattern matching the variant, optionally binding its fields.
+    fn pattern(&self, bind_fields: bool) -> TokenStream {
+        let variant_ident = &self.variant.ident;
+        let field_idents = self.fields.iter().map(|field| &field.ident);
+
+        match &self.variant.fields {
+            Fields::Named(_) if bind_fields => {
+                quote! { Self::#variant_ident { #(#field_idents),* } }
+            }
+            Fields::Named(_) => quote! { Self::#variant_ident { .. } },
+            Fields::Unit | Fields::Unnamed(_) => quote! { Self::#variant_ident },
+        }
+    }
+
+    /// The expression creating the variant's `ParameterizedRoute`.
+    fn template(&self) -> TokenStream {
+        let normalized = &self.normalized;
+        let path = &self.path;
+
+        quote! { ::web_route::__private::parameterized_route(#normalized, #path) }
+    }
+
+    /// The expression creating the variant from the captured `params`,
+    /// returning `None` from the enclosing function if a field is missing or
+    /// cannot be parsed.
+    fn recognized_variant(&self) -> TokenStream {
+        let variant_ident = &self.variant.ident;
+        let field_values = self.fields.iter().map(|field| {
+            let field_ident = &field.ident;
+            let param = field_name(field);
+
+            if is_option(&field.ty) {
+                quote_spanned! {field.span()=>
+                    #field_ident: ::web_route::__private::parse_optional_param(params, #param)?
+                }
+            } else {
+                quote_spanned! {field.span()=>
+                    #field_ident: ::web_route::__private::parse_param(params, #param)?
+                }
+            }
+        });
+
+        match &self.variant.fields {
+            Fields::Named(_) => quote! { Self::#variant_ident { #(#field_values),* } },
+            Fields::Unit | Fields::Unnamed(_) => quote! { Self::#variant_ident },
+        }
+    }
+}
+
+/// Reports every variant whose template (or one of its optional forms) matches
+/// the same routes as that of an earlier variant, which would make it
+/// unreachable when recognizing a route.
+fn ambiguous_routes(routes: &[VariantRoute<'_>]) -> Vec<syn::Error> {
+    let mut errors = Vec::new();
+    for (index, route) in routes.iter().enumerate() {
+        let shapes = route.parsed_template.to_shapes();
+
+        if let Some(earlier) = routes[..index].iter().find(|earlier| {
+            earlier
+                .parsed_template
+                .to_shapes()
+                .iter()
+                .any(|shape| shapes.contains(shape))
+        }) {
+            errors.push(syn::Error::new(
+                route.variant.ident.span(),
+                format!(
+                    "the route of `{}` matches the same routes as that of `{}`",
+                    route.variant.ident, earlier.variant.ident
+                ),
+            ));
+        }
+    }
+
+    errors
+}